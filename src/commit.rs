@@ -0,0 +1,180 @@
+use crate::config::{Config, ScopePolicy};
+use crate::conventional::{BREAKING_CHANGE, CommitMessage, Footer, Header, Separator};
+use crate::validator::{
+    DICTIONARY, scope_validator, validate_body_line_length, validate_not_empty, validate_spelling,
+    validate_summary_length, validate_summary_punctuation,
+};
+use crossterm::style::Stylize;
+use inquire::validator::ErrorMessage::Custom;
+use inquire::validator::Validation;
+use inquire::{Confirm, CustomUserError, Editor, Select, Text};
+use std::io::{Error, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+///
+/// Walks the user through the parts of a conventional commit message.
+///
+/// The prompts ask, in order, for:
+/// 1. The type, selected among the configured types.
+/// 2. The scope, checked against the configured scopes and scope policy (skipped when forbidden).
+/// 3. The summary, checked for emptiness, length, punctuation and spelling.
+/// 4. The body, written in the user's editor and checked for line length.
+/// 5. Whether the change is breaking, and its description.
+/// 6. The issues closed by the commit, written as `Closes #<issue>` footers.
+///
+/// # Errors
+/// - If a prompt fails or is canceled by the user.
+///
+pub fn compose(config: &Config) -> Result<CommitMessage, Error> {
+    let commit_type = Select::new("Type of change?", config.types.clone())
+        .prompt()
+        .map_err(Error::other)?;
+
+    let scope = if config.scope_policy == ScopePolicy::Forbidden {
+        None
+    } else {
+        let help = if config.scopes.is_empty() {
+            String::from("Leave empty for no scope")
+        } else {
+            format!("One of: {}", config.scopes.join(", "))
+        };
+        let scopes = config.scopes.clone();
+        let scope = Text::new("Scope?")
+            .with_help_message(&help)
+            .with_autocomplete(move |input: &str| -> Result<Vec<String>, CustomUserError> {
+                let current = input.rsplit(',').next().unwrap_or_default().trim();
+                Ok(scopes
+                    .iter()
+                    .filter(|scope| scope.starts_with(current))
+                    .cloned()
+                    .collect())
+            })
+            .with_validator(scope_validator(config))
+            .prompt()
+            .map_err(Error::other)?;
+        let scope = scope.trim();
+        (!scope.is_empty()).then(|| scope.to_string())
+    };
+
+    let mut summary = Text::new("Summary?")
+        .with_validator(validate_not_empty)
+        .with_validator(validate_summary_length)
+        .with_validator(validate_summary_punctuation);
+    if Path::new(DICTIONARY).is_file() {
+        summary = summary.with_validator(validate_spelling);
+    }
+    let description = summary.prompt().map_err(Error::other)?.trim().to_string();
+
+    let body = Editor::new("Body?")
+        .with_help_message("Explain what and why, leave empty to skip")
+        .with_validator(validate_body_line_length)
+        .prompt()
+        .map_err(Error::other)?;
+    let body = body.trim();
+
+    let mut footers = Vec::new();
+    let breaking = Confirm::new("Is this a breaking change?")
+        .with_default(false)
+        .prompt()
+        .map_err(Error::other)?;
+    if breaking {
+        let change = Text::new("Describe the breaking change:")
+            .with_validator(validate_not_empty)
+            .prompt()
+            .map_err(Error::other)?;
+        footers.push(Footer {
+            token: BREAKING_CHANGE.to_string(),
+            separator: Separator::Colon,
+            value: change.trim().to_string(),
+        });
+    }
+
+    let issues = Text::new("Issues closed by this commit?")
+        .with_help_message("e.g. 12, 34 - leave empty to skip")
+        .with_validator(validate_issues)
+        .prompt()
+        .map_err(Error::other)?;
+    for issue in issue_numbers(&issues) {
+        footers.push(Footer {
+            token: String::from("Closes"),
+            separator: Separator::Hash,
+            value: issue.to_string(),
+        });
+    }
+
+    Ok(CommitMessage {
+        header: Header {
+            commit_type,
+            scope,
+            breaking,
+            description,
+        },
+        body: (!body.is_empty()).then(|| body.to_string()),
+        footers,
+    })
+}
+
+///
+/// Composes a commit message, shows a preview and commits the staged changes with it.
+///
+/// Returns `false` when the user declines the preview or when `git commit` fails.
+///
+/// # Errors
+/// - If a prompt fails or is canceled by the user.
+/// - If `git` cannot be started.
+///
+pub fn commit(config: &Config) -> Result<bool, Error> {
+    let message = compose(config)?;
+
+    println!("\n{}\n", "Commit message preview:".bold());
+    for line in message.to_string().lines() {
+        println!("    {line}");
+    }
+    println!();
+    let confirmed = Confirm::new("Commit with this message?")
+        .with_default(true)
+        .prompt()
+        .map_err(Error::other)?;
+    if !confirmed {
+        return Ok(false);
+    }
+    git_commit(&message)
+}
+
+///
+/// Runs `git commit -F -`, giving it the message on its standard input.
+///
+/// The `commit-msg` hook, if installed, checks the message as for any other commit.
+///
+/// # Errors
+/// - If `git` cannot be started or its standard input cannot be written.
+///
+pub fn git_commit(message: &CommitMessage) -> Result<bool, Error> {
+    let mut child = Command::new("git")
+        .args(["commit", "-F", "-"])
+        .stdin(Stdio::piped())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        writeln!(stdin, "{message}")?;
+    }
+    Ok(child.wait()?.success())
+}
+
+fn validate_issues(input: &str) -> Result<Validation, CustomUserError> {
+    for issue in input.split([',', ' ']).filter(|issue| !issue.is_empty()) {
+        let number = issue.trim_start_matches('#');
+        if number.is_empty() || !number.chars().all(|c| c.is_ascii_digit()) {
+            let message = Custom(format!("'{issue}' is not an issue number"));
+            return Ok(Validation::Invalid(message));
+        }
+    }
+    Ok(Validation::Valid)
+}
+
+fn issue_numbers(input: &str) -> impl Iterator<Item = &str> {
+    input
+        .split([',', ' '])
+        .map(|issue| issue.trim_start_matches('#'))
+        .filter(|issue| !issue.is_empty())
+}
@@ -0,0 +1,600 @@
+use crate::files::glob_set;
+use crate::hooks::Hook;
+use crate::hooks::Language;
+use crate::hooks::OutputFormat;
+use crate::snapshot::SnapshotMode;
+use crate::tools::MissingTools;
+use crate::validator::VALID_TYPES;
+use serde::{Deserialize, Deserializer};
+use std::collections::BTreeMap;
+use std::env::current_dir;
+use std::fs::read_to_string;
+use std::io::{Error, ErrorKind};
+use std::path::{Path, PathBuf};
+
+/// The name of the project policy file searched for in the repository.
+///
+/// # Example
+/// ```rust
+/// use breathes::config::CONFIG_FILE;
+/// assert_eq!(CONFIG_FILE, "breathes.toml");
+/// ```
+pub const CONFIG_FILE: &str = "breathes.toml";
+
+///
+/// The project policy loaded from `breathes.toml`.
+///
+/// Every key is optional. A missing file or a missing key falls back to the
+/// built-in defaults, so `Config::default()` describes a project without any
+/// policy file.
+///
+/// # Keys
+/// * `scopes`: The commit scopes allowed in this repository. When empty, any scope is allowed.
+/// * `scope_policy`: Whether a scope is `"optional"` (the default), `"required"` or `"forbidden"`.
+/// * `multiple_scopes`: Whether a commit may name several comma-separated scopes (`false` by default).
+/// * `types`: The commit types allowed in this repository (defaults to [`VALID_TYPES`]).
+/// * `me`: The owner of the repository.
+/// * `repository`: The name of the repository.
+/// * `builtin_hooks`: Whether the `[[hooks]]` tables are merged with (`"merge"`, the default)
+///   or replace (`"replace"`) the built-in hooks of their language.
+/// * `[[hooks]]`: Additional hooks, see [`HookConfig`].
+/// * `[overrides.<id>]`: Changes to a hook selected by its identifier, see [`HookOverride`].
+/// * `[run]`: How the hooks run, see [`RunConfig`].
+///
+/// Unknown keys are rejected, so a typo in `breathes.toml` is reported instead
+/// of being silently ignored.
+///
+/// # Example
+/// ```rust
+/// use breathes::config::Config;
+///
+/// let config: Config = r#"
+/// scopes = ["lib", "api"]
+/// types = ["feat", "fix", "revert"]
+/// "#.parse().expect("valid config");
+///
+/// assert_eq!(config.scopes, ["lib", "api"]);
+/// assert!(config.types.iter().any(|t| t == "revert"));
+/// assert!(config.me.is_none());
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub scopes: Vec<String>,
+    pub scope_policy: ScopePolicy,
+    pub multiple_scopes: bool,
+    pub types: Vec<String>,
+    pub me: Option<String>,
+    pub repository: Option<String>,
+    pub builtin_hooks: BuiltinHooks,
+    pub hooks: Vec<HookConfig>,
+    pub overrides: BTreeMap<String, HookOverride>,
+    pub run: RunConfig,
+    /// The file this configuration was read from, if any.
+    #[serde(skip)]
+    pub path: Option<PathBuf>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            scopes: Vec::new(),
+            scope_policy: ScopePolicy::default(),
+            multiple_scopes: false,
+            types: VALID_TYPES.iter().map(ToString::to_string).collect(),
+            me: None,
+            repository: None,
+            builtin_hooks: BuiltinHooks::default(),
+            hooks: Vec::new(),
+            overrides: BTreeMap::new(),
+            run: RunConfig::default(),
+            path: None,
+        }
+    }
+}
+
+impl std::str::FromStr for Config {
+    type Err = Error;
+
+    fn from_str(content: &str) -> Result<Self, Self::Err> {
+        let config: Self = toml::from_str(content)
+            .map_err(|e| Error::new(ErrorKind::InvalidData, e.to_string()))?;
+        config.check()?;
+        Ok(config)
+    }
+}
+
+impl Config {
+    ///
+    /// Loads the configuration of the repository containing the current directory.
+    ///
+    /// Returns `Config::default()` when no `breathes.toml` is found.
+    ///
+    /// # Errors
+    /// - If the current directory cannot be read.
+    /// - If `breathes.toml` exists but cannot be read or is malformed.
+    ///
+    pub fn load() -> Result<Self, Error> {
+        match Self::discover(&current_dir()?) {
+            Some(path) => Self::from_file(&path),
+            None => Ok(Self::default()),
+        }
+    }
+
+    ///
+    /// Reads and parses a configuration file.
+    ///
+    /// # Errors
+    /// - If the file cannot be read.
+    /// - If the file contains unknown keys or malformed values. The error message
+    ///   is prefixed with the path of the file.
+    ///
+    pub fn from_file(path: &Path) -> Result<Self, Error> {
+        let content = read_to_string(path)?;
+        let mut config: Self = content
+            .parse()
+            .map_err(|e: Error| Error::new(e.kind(), format!("{}: {e}", path.display())))?;
+        config.path = Some(path.to_path_buf());
+        Ok(config)
+    }
+
+    ///
+    /// Searches for `breathes.toml` from `start` up to the repository root.
+    ///
+    /// The search walks the parent directories of `start` and stops at the first
+    /// directory containing a `.git` entry (a directory, or a file for worktrees
+    /// and submodules), so a policy file outside the repository is never used.
+    ///
+    #[must_use]
+    pub fn discover(start: &Path) -> Option<PathBuf> {
+        for dir in start.ancestors() {
+            let candidate = dir.join(CONFIG_FILE);
+            if candidate.is_file() {
+                return Some(candidate);
+            }
+            if dir.join(".git").exists() {
+                return None;
+            }
+        }
+        None
+    }
+
+    ///
+    /// Returns the directory containing the configuration file, if any.
+    ///
+    #[must_use]
+    pub fn root(&self) -> Option<&Path> {
+        self.path.as_deref().and_then(Path::parent)
+    }
+
+    fn check(&self) -> Result<(), Error> {
+        if self.types.is_empty() {
+            return Err(invalid("`types` cannot be empty"));
+        }
+        check_names("types", &self.types)?;
+        check_names("scopes", &self.scopes)?;
+        if self.run.timeout == Some(0) {
+            return Err(invalid("`run.timeout` must be greater than 0"));
+        }
+        if self.run.jobs == Some(0) {
+            return Err(invalid("`run.jobs` must be greater than 0"));
+        }
+        let mut ids: Vec<(Language, String)> = Language::ALL
+            .into_iter()
+            .flat_map(|language| {
+                Hook::get(language)
+                    .into_iter()
+                    .map(move |hook| (language, hook.id.into_owned()))
+            })
+            .collect();
+        let mut declared: Vec<(Language, String)> = Vec::new();
+        for hook in &self.hooks {
+            if hook.timeout == Some(0) {
+                return Err(invalid(&format!(
+                    "hook '{}' has a `timeout` of 0",
+                    hook.description
+                )));
+            }
+            if hook.command.trim().is_empty() {
+                return Err(invalid(&format!(
+                    "hook '{}' has an empty `command`",
+                    hook.description
+                )));
+            }
+            glob_set(&hook.files)
+                .map_err(|e| invalid(&format!("hook '{}': {e}", hook.description)))?;
+            let id = Hook::from(hook).id.into_owned();
+            let builtin = self.builtin_hooks == BuiltinHooks::Merge
+                && Hook::get(hook.language).iter().any(|other| other.id == id);
+            if builtin || declared.contains(&(hook.language, id.clone())) {
+                return Err(invalid(&format!(
+                    "hook '{id}' is declared twice for {}",
+                    hook.language
+                )));
+            }
+            declared.push((hook.language, id.clone()));
+            ids.push((hook.language, id));
+        }
+        for (id, over) in &self.overrides {
+            if !ids.iter().any(|(_, known)| known == id) {
+                return Err(invalid(&format!("unknown hook '{id}' in `overrides`")));
+            }
+            if over.command.as_ref().is_some_and(|c| c.trim().is_empty()) {
+                return Err(invalid(&format!("hook '{id}' has an empty `command`")));
+            }
+            if over.timeout == Some(0) {
+                return Err(invalid(&format!("hook '{id}' has a `timeout` of 0")));
+            }
+            if let Some(files) = &over.files {
+                glob_set(files).map_err(|e| invalid(&format!("hook '{id}': {e}")))?;
+            }
+        }
+        // A hook waits only for the hooks of its own language, run in the same project.
+        let declared = self.hooks.iter().map(|hook| {
+            (
+                hook.language,
+                Hook::from(hook).id.into_owned(),
+                &hook.depends_on,
+            )
+        });
+        let overridden = self.overrides.iter().flat_map(|(id, over)| {
+            ids.iter()
+                .filter(move |(_, known)| known == id)
+                .filter_map(move |(language, _)| {
+                    Some((*language, id.clone(), over.depends_on.as_ref()?))
+                })
+        });
+        for (language, id, dependencies) in declared.chain(overridden) {
+            for dependency in dependencies {
+                if ids.contains(&(language, dependency.clone())) {
+                    continue;
+                }
+                if ids.iter().any(|(_, known)| known == dependency) {
+                    return Err(invalid(&format!(
+                        "hook '{id}' depends on '{dependency}', which is not a {language} hook"
+                    )));
+                }
+                return Err(invalid(&format!(
+                    "unknown hook '{dependency}' in `depends_on`"
+                )));
+            }
+        }
+        for language in Language::ALL {
+            if let Some(id) = find_cycle(&Hook::configured(language, self)) {
+                return Err(invalid(&format!(
+                    "hook '{id}' depends on itself through `depends_on`"
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+///
+/// Whether commits must, may or must not name a scope.
+///
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ScopePolicy {
+    /// A scope may be given.
+    #[default]
+    Optional,
+    /// A scope must be given.
+    Required,
+    /// A scope must not be given.
+    Forbidden,
+}
+
+///
+/// How the hooks declared in `breathes.toml` combine with the built-in ones.
+///
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BuiltinHooks {
+    /// Run the declared hooks after the built-in hooks of their language.
+    #[default]
+    Merge,
+    /// Run only the declared hooks for every language declaring at least one.
+    Replace,
+}
+
+///
+/// How the hooks run, set in the `[run]` table of `breathes.toml`.
+///
+/// # Keys
+/// * `timeout`: The number of seconds after which a hook without a timeout of its own is
+///   killed, with every process it started. Hooks run without a deadline by default.
+/// * `jobs`: The number of hooks allowed to run at the same time, across every language
+///   (the number of cores by default).
+/// * `fail_fast`: Stops the run at the first failure: the running hooks are killed and
+///   reported as cancelled, the others are skipped (`false` by default).
+/// * `staged`: Runs only the hooks of the projects touched by the staged changes (`false` by
+///   default).
+/// * `snapshot`: What the hooks check: `"none"` for the working tree (the default), `"stash"`
+///   to set the unstaged changes aside while they run, or `"index"` to check a copy of the
+///   index in a temporary directory.
+/// * `missing_tools`: What happens to a hook whose tool is not installed: `"fail"` reports it
+///   as `tool missing` and fails the run (the default), `"skip"` skips it. Either way, the
+///   report tells how to install the tool.
+///
+/// `sarif` is not read from the file: it is set when a SARIF report is requested, and makes
+/// the built-in Cargo hooks print their findings as JSON.
+///
+/// # Example
+/// ```rust
+/// use breathes::config::Config;
+/// use breathes::hooks::{Hook, Language};
+/// use std::time::Duration;
+///
+/// let config: Config = r#"
+/// [run]
+/// timeout = 600
+///
+/// [overrides.rust-test]
+/// timeout = 1800
+/// "#.parse().unwrap();
+///
+/// let hooks = Hook::configured(Language::Rust, &config);
+/// let timeout = |id: &str| hooks.iter().find(|hook| hook.id == id).unwrap().timeout;
+/// assert_eq!(timeout("rust-test"), Some(Duration::from_secs(1800)));
+/// assert_eq!(timeout("rust-fmt"), Some(Duration::from_secs(600)));
+/// assert!("[run]\ntimeout = 0".parse::<Config>().is_err());
+/// assert!("[run]\nfail_fast = true".parse::<Config>().unwrap().run.fail_fast);
+///
+/// let clippy = |config: &Config| {
+///     let hooks = Hook::configured(Language::Rust, config);
+///     hooks.into_iter().find(|hook| hook.id == "rust-clippy").unwrap().command
+/// };
+/// let mut config = Config::default();
+/// assert!(!clippy(&config).contains("--message-format=json"));
+/// config.run.sarif = true;
+/// assert!(clippy(&config).starts_with("cargo clippy --message-format=json -- "));
+///
+/// // `rust-test` already depends on `rust-check`.
+/// let cycle = "[overrides.rust-check]\ndepends_on = [\"rust-test\"]";
+/// assert!(cycle.parse::<Config>().is_err());
+///
+/// // A hook only waits for the hooks of its own language.
+/// let other = "[overrides.go-test]\ndepends_on = [\"python-test\"]";
+/// assert!(other.parse::<Config>().is_err());
+/// ```
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct RunConfig {
+    pub timeout: Option<u64>,
+    pub jobs: Option<usize>,
+    pub fail_fast: bool,
+    pub staged: bool,
+    pub snapshot: SnapshotMode,
+    pub missing_tools: MissingTools,
+    #[serde(skip)]
+    pub sarif: bool,
+}
+
+///
+/// A hook declared with a `[[hooks]]` table in `breathes.toml`.
+///
+/// # Keys
+/// * `language`: The language the hook runs for (case-insensitive, e.g. `"rust"`).
+/// * `id`: The identifier of the hook (optional, `<language>-<file>` by default), unique among
+///   the hooks of its language, built-in hooks included unless they are replaced.
+/// * `description`: The message displayed while the hook runs.
+/// * `command`: The shell command to execute.
+/// * `success`: The message describing a successful run (optional).
+/// * `failure`: The message describing a failed run (optional).
+/// * `file`: The log file name (optional, derived from the description by default).
+/// * `warn_only`: Report failures without failing the run (optional, `false` by default).
+/// * `output`: The format of the standard output, `"text"` (the default), `"cargo-json"` or
+///   `"tsc"`, used to export findings as SARIF.
+/// * `timeout`: The number of seconds after which the command is killed (optional, see [`RunConfig`]).
+/// * `depends_on`: The ids of the hooks of the same language which must pass before this one
+///   starts (optional).
+/// * `parallel`: Whether the hook may run alongside other hooks (optional, `true` by default).
+/// * `files`: The glob patterns of the files replacing `{files}` in the command, such as
+///   `["*.swift"]` (optional, every file by default). With `[run] staged`, only the staged
+///   files are given, and the hook is skipped when none matches.
+///
+/// # Example
+/// ```toml
+/// [[hooks]]
+/// language = "rust"
+/// description = "Checking dependency licenses"
+/// command = "cargo deny check"
+/// success = "Licenses are allowed"
+/// failure = "License violations found"
+/// file = "deny.log"
+/// ```
+///
+/// Two hooks of a language cannot share an id, since `[overrides]` and `depends_on` could
+/// not tell them apart:
+/// ```rust
+/// use breathes::config::Config;
+///
+/// let twice = r#"
+/// [[hooks]]
+/// language = "go"
+/// description = "Check"
+/// command = "go vet ./..."
+///
+/// [[hooks]]
+/// language = "go"
+/// description = "Check"
+/// command = "staticcheck ./..."
+/// "#;
+/// assert!(twice.parse::<Config>().is_err());
+///
+/// let builtin = "[[hooks]]\nlanguage = \"rust\"\nid = \"rust-clippy\"\ndescription = \"Lint\"\ncommand = \"cargo clippy\"";
+/// assert!(builtin.parse::<Config>().is_err());
+/// assert!(format!("builtin_hooks = \"replace\"\n{builtin}").parse::<Config>().is_ok());
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct HookConfig {
+    #[serde(deserialize_with = "language")]
+    pub language: Language,
+    pub id: Option<String>,
+    pub description: String,
+    pub command: String,
+    pub success: Option<String>,
+    pub failure: Option<String>,
+    pub file: Option<String>,
+    #[serde(default)]
+    pub warn_only: bool,
+    #[serde(default)]
+    pub output: OutputFormat,
+    pub timeout: Option<u64>,
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    #[serde(default = "parallel")]
+    pub parallel: bool,
+    #[serde(default)]
+    pub files: Vec<String>,
+}
+
+///
+/// Changes applied to a hook, built-in or declared, selected by its identifier.
+///
+/// # Keys
+/// * `enabled`: Set to `false` to never run the hook.
+/// * `command`: Replaces the command of the hook.
+/// * `warn_only`: Report failures without failing the run.
+/// * `output`: Changes the format of the standard output, see [`HookConfig`].
+/// * `timeout`: Changes the number of seconds after which the command is killed.
+/// * `depends_on`: Replaces the ids of the hooks of the same language which must pass before
+///   this one starts.
+/// * `parallel`: Whether the hook may run alongside other hooks.
+/// * `files`: Replaces the glob patterns of the files given to `{files}`.
+///
+/// # Example
+/// ```rust
+/// use breathes::config::Config;
+/// use breathes::hooks::{Hook, Language};
+///
+/// let config: Config = r#"
+/// [overrides.rust-outdated]
+/// enabled = false
+///
+/// [overrides.rust-clippy]
+/// command = "cargo clippy -- -D warnings"
+/// warn_only = true
+/// "#.parse().unwrap();
+///
+/// let hooks = Hook::configured(Language::Rust, &config);
+/// assert!(hooks.iter().all(|hook| hook.id != "rust-outdated"));
+/// let clippy = hooks.iter().find(|hook| hook.id == "rust-clippy").unwrap();
+/// assert!(clippy.warn_only);
+/// assert!("[overrides.rust-typo]\nenabled = false".parse::<Config>().is_err());
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct HookOverride {
+    pub enabled: bool,
+    pub command: Option<String>,
+    pub warn_only: Option<bool>,
+    pub output: Option<OutputFormat>,
+    pub timeout: Option<u64>,
+    pub depends_on: Option<Vec<String>>,
+    pub parallel: Option<bool>,
+    pub files: Option<Vec<String>>,
+}
+
+impl Default for HookOverride {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            command: None,
+            warn_only: None,
+            output: None,
+            timeout: None,
+            depends_on: None,
+            parallel: None,
+            files: None,
+        }
+    }
+}
+
+const fn parallel() -> bool {
+    true
+}
+
+fn language<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Language, D::Error> {
+    let name = String::deserialize(deserializer)?;
+    name.parse().map_err(serde::de::Error::custom)
+}
+
+/// Returns the id of a hook depending on itself, directly or through other hooks.
+fn find_cycle(hooks: &[Hook]) -> Option<String> {
+    fn visit<'a>(
+        id: &'a str,
+        hooks: &'a [Hook],
+        path: &mut Vec<&'a str>,
+        done: &mut Vec<&'a str>,
+    ) -> Option<String> {
+        if path.contains(&id) {
+            return Some(id.to_string());
+        }
+        if done.contains(&id) {
+            return None;
+        }
+        path.push(id);
+        for hook in hooks.iter().filter(|hook| hook.id == id) {
+            for dependency in &hook.depends_on {
+                if let Some(cycle) = visit(dependency, hooks, path, done) {
+                    return Some(cycle);
+                }
+            }
+        }
+        path.pop();
+        done.push(id);
+        None
+    }
+    let mut done = Vec::new();
+    hooks
+        .iter()
+        .find_map(|hook| visit(&hook.id, hooks, &mut Vec::new(), &mut done))
+}
+
+fn check_names(key: &str, names: &[String]) -> Result<(), Error> {
+    for (i, name) in names.iter().enumerate() {
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(invalid(&format!(
+                "invalid value '{name}' in `{key}`: only letters, digits, '-' and '_' are allowed"
+            )));
+        }
+        if names[..i].contains(name) {
+            return Err(invalid(&format!("duplicate value '{name}' in `{key}`")));
+        }
+    }
+    Ok(())
+}
+
+fn invalid(message: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CONFIG_FILE, Config};
+    use crate::testing::Fixture;
+
+    #[test]
+    fn discover_stops_at_the_repository_root() {
+        let fixture = Fixture::new("config-discover");
+        fixture
+            .write(CONFIG_FILE, "")
+            .write("repo/.git/HEAD", "")
+            .write("repo/src/lib.rs", "");
+        let repo = fixture.path().join("repo");
+        assert!(Config::discover(&repo.join("src")).is_none());
+
+        fixture.write(&format!("repo/{CONFIG_FILE}"), "");
+        assert_eq!(
+            Config::discover(&repo.join("src")),
+            Some(repo.join(CONFIG_FILE))
+        );
+    }
+}
@@ -0,0 +1,385 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// The footer token announcing a breaking change.
+pub const BREAKING_CHANGE: &str = "BREAKING CHANGE";
+
+///
+/// The first line of a conventional commit: `type(scope)!: description`.
+///
+/// * `commit_type`: The kind of change (`feat`, `fix`, ...).
+/// * `scope`: The section of the codebase affected, if any.
+/// * `breaking`: Whether the `!` marker announces a breaking change.
+/// * `description`: The summary of the change.
+///
+/// # Example
+/// ```rust
+/// use breathes::conventional::Header;
+///
+/// let header: Header = "feat(api)!: drop the v1 routes".parse().unwrap();
+/// assert_eq!(header.commit_type, "feat");
+/// assert_eq!(header.scope.as_deref(), Some("api"));
+/// assert!(header.breaking);
+/// assert_eq!(header.to_string(), "feat(api)!: drop the v1 routes");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Header {
+    pub commit_type: String,
+    pub scope: Option<String>,
+    pub breaking: bool,
+    pub description: String,
+}
+
+///
+/// A trailer of a conventional commit, such as `Refs: #12` or `BREAKING CHANGE: ...`.
+///
+/// * `token`: The name of the footer (`Refs`, `Reviewed-by`, `BREAKING CHANGE`, ...).
+/// * `separator`: The separator written between the token and the value.
+/// * `value`: The value, which may span several lines.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Footer {
+    pub token: String,
+    pub separator: Separator,
+    pub value: String,
+}
+
+///
+/// The separator between the token and the value of a [`Footer`].
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Separator {
+    /// `Token: value`
+    #[default]
+    Colon,
+    /// `Token #value`, used to reference issues.
+    Hash,
+}
+
+///
+/// A commit message following the Conventional Commits 1.0 specification.
+///
+/// Parsing expects the text git stores in the commit, without comment lines.
+/// Formatting with `Display` writes the message back in the canonical layout: the
+/// header, then the body and the footers separated by blank lines.
+///
+/// # Example
+/// ```rust
+/// use breathes::conventional::CommitMessage;
+///
+/// let text = "fix(parser): accept empty scopes\n\nThe parser panicked on `()`.\n\nRefs #42\nBREAKING CHANGE: empty scopes are now rejected";
+/// let message: CommitMessage = text.parse().unwrap();
+///
+/// assert_eq!(message.header.commit_type, "fix");
+/// assert_eq!(message.body.as_deref(), Some("The parser panicked on `()`."));
+/// assert_eq!(message.footers.len(), 2);
+/// assert!(message.is_breaking());
+/// assert_eq!(message.to_string(), text);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CommitMessage {
+    pub header: Header,
+    pub body: Option<String>,
+    pub footers: Vec<Footer>,
+}
+
+///
+/// The reasons why a commit message does not follow the specification.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// The message has no header.
+    Empty,
+    /// The header does not start with a type.
+    MissingType,
+    /// The scope has no closing parenthesis.
+    UnclosedScope,
+    /// The scope is empty.
+    EmptyScope,
+    /// The type and scope are not followed by a colon.
+    MissingColon,
+    /// The colon is not followed by a space.
+    MissingSpace,
+    /// The description is empty.
+    EmptyDescription,
+    /// The header is not followed by a blank line.
+    MissingBlankLine,
+}
+
+///
+/// An error returned when a commit message does not follow the specification.
+///
+/// * `line`: The 1-based line of the error in the parsed text.
+/// * `column`: The 1-based column of the error, counted in characters.
+/// * `kind`: What went wrong.
+///
+/// # Example
+/// ```rust
+/// use breathes::conventional::{CommitMessage, ParseErrorKind};
+///
+/// let error = "feat(api: add routes".parse::<CommitMessage>().unwrap_err();
+/// assert_eq!(error.kind, ParseErrorKind::UnclosedScope);
+/// assert_eq!((error.line, error.column), (1, 5));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub kind: ParseErrorKind,
+}
+
+impl Display for ParseErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "The commit message is empty"),
+            Self::MissingType => write!(f, "The header must start with a type"),
+            Self::UnclosedScope => write!(f, "The scope is missing its closing parenthesis"),
+            Self::EmptyScope => write!(f, "The scope cannot be empty"),
+            Self::MissingColon => write!(f, "Expected ':' after the type and scope"),
+            Self::MissingSpace => write!(f, "Expected a space after ':'"),
+            Self::EmptyDescription => write!(f, "The description cannot be empty"),
+            Self::MissingBlankLine => {
+                write!(f, "A blank line must separate the header from the body")
+            }
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.kind)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl FromStr for Header {
+    type Err = ParseError;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let error = |column: usize, kind: ParseErrorKind| ParseError {
+            line: 1,
+            column,
+            kind,
+        };
+        let chars: Vec<char> = line.chars().collect();
+        if line.trim().is_empty() {
+            return Err(error(1, ParseErrorKind::Empty));
+        }
+        let mut i = chars
+            .iter()
+            .take_while(|c| c.is_alphanumeric() || **c == '-' || **c == '_')
+            .count();
+        if i == 0 {
+            return Err(error(1, ParseErrorKind::MissingType));
+        }
+        let commit_type: String = chars[..i].iter().collect();
+
+        let mut scope = None;
+        if chars.get(i) == Some(&'(') {
+            let Some(len) = chars[i + 1..].iter().position(|c| *c == ')') else {
+                return Err(error(i + 1, ParseErrorKind::UnclosedScope));
+            };
+            let value: String = chars[i + 1..i + 1 + len].iter().collect();
+            if value.trim().is_empty() {
+                return Err(error(i + 2, ParseErrorKind::EmptyScope));
+            }
+            scope = Some(value);
+            i += len + 2;
+        }
+        let breaking = chars.get(i) == Some(&'!');
+        if breaking {
+            i += 1;
+        }
+        if chars.get(i) != Some(&':') {
+            return Err(error(i + 1, ParseErrorKind::MissingColon));
+        }
+        if chars.get(i + 1) != Some(&' ') {
+            return Err(error(i + 2, ParseErrorKind::MissingSpace));
+        }
+        let description: String = chars[i + 2..].iter().collect();
+        if description.trim().is_empty() {
+            return Err(error(i + 3, ParseErrorKind::EmptyDescription));
+        }
+        Ok(Self {
+            commit_type,
+            scope,
+            breaking,
+            description,
+        })
+    }
+}
+
+impl Display for Header {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.commit_type)?;
+        if let Some(scope) = &self.scope {
+            write!(f, "({scope})")?;
+        }
+        if self.breaking {
+            write!(f, "!")?;
+        }
+        write!(f, ": {}", self.description)
+    }
+}
+
+impl Header {
+    ///
+    /// Returns the column, counted in characters from 1, where the description starts.
+    ///
+    #[must_use]
+    pub fn description_column(&self) -> usize {
+        self.to_string().chars().count() - self.description.chars().count() + 1
+    }
+}
+
+impl Footer {
+    ///
+    /// Parses the first line of a footer, or returns `None` if `line` is not a footer.
+    ///
+    /// ```rust
+    /// use breathes::conventional::{Footer, Separator};
+    ///
+    /// let footer = Footer::parse("Refs #42").unwrap();
+    /// assert_eq!((footer.token.as_str(), footer.separator, footer.value.as_str()), ("Refs", Separator::Hash, "42"));
+    /// assert!(Footer::parse("Not a footer").is_none());
+    /// ```
+    #[must_use]
+    pub fn parse(line: &str) -> Option<Self> {
+        let (token, separator, value) = if let Some(value) = line
+            .strip_prefix(BREAKING_CHANGE)
+            .or_else(|| line.strip_prefix("BREAKING-CHANGE"))
+            .and_then(|rest| rest.strip_prefix(": "))
+        {
+            (&line[..BREAKING_CHANGE.len()], Separator::Colon, value)
+        } else {
+            let end = line.find([':', ' '])?;
+            let token = &line[..end];
+            if token.is_empty() || !token.chars().all(|c| c.is_alphanumeric() || c == '-') {
+                return None;
+            }
+            let rest = &line[end..];
+            if let Some(value) = rest.strip_prefix(": ") {
+                (token, Separator::Colon, value)
+            } else {
+                (token, Separator::Hash, rest.strip_prefix(" #")?)
+            }
+        };
+        Some(Self {
+            token: token.to_string(),
+            separator,
+            value: value.to_string(),
+        })
+    }
+
+    ///
+    /// Whether this footer announces a breaking change.
+    ///
+    #[must_use]
+    pub fn is_breaking(&self) -> bool {
+        self.token == BREAKING_CHANGE || self.token == "BREAKING-CHANGE"
+    }
+}
+
+impl Display for Footer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.separator {
+            Separator::Colon => write!(f, "{}: {}", self.token, self.value),
+            Separator::Hash => write!(f, "{} #{}", self.token, self.value),
+        }
+    }
+}
+
+impl FromStr for CommitMessage {
+    type Err = ParseError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let lines: Vec<&str> = text.lines().collect();
+        let header: Header = lines.first().copied().unwrap_or_default().parse()?;
+        let rest = lines.get(1..).unwrap_or_default();
+        if rest.first().is_some_and(|line| !line.trim().is_empty()) {
+            return Err(ParseError {
+                line: 2,
+                column: 1,
+                kind: ParseErrorKind::MissingBlankLine,
+            });
+        }
+        let end = rest
+            .iter()
+            .rposition(|line| !line.trim().is_empty())
+            .map_or(0, |i| i + 1);
+        let rest = &rest[..end];
+        let (body, footers) = split_footers(rest);
+        let body = body
+            .iter()
+            .skip_while(|line| line.trim().is_empty())
+            .copied()
+            .collect::<Vec<_>>()
+            .join("\n");
+        let body = body.trim_end().to_string();
+        Ok(Self {
+            header,
+            body: (!body.is_empty()).then_some(body),
+            footers,
+        })
+    }
+}
+
+impl Display for CommitMessage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.header)?;
+        if let Some(body) = &self.body {
+            write!(f, "\n\n{body}")?;
+        }
+        for (i, footer) in self.footers.iter().enumerate() {
+            let separator = if i == 0 { "\n\n" } else { "\n" };
+            write!(f, "{separator}{footer}")?;
+        }
+        Ok(())
+    }
+}
+
+impl CommitMessage {
+    ///
+    /// Whether the commit announces a breaking change, with the `!` marker or a footer.
+    ///
+    #[must_use]
+    pub fn is_breaking(&self) -> bool {
+        self.header.breaking || self.footers.iter().any(Footer::is_breaking)
+    }
+
+    ///
+    /// Returns the number of lines of the body, as laid out by `Display`.
+    ///
+    #[must_use]
+    pub fn body_line_count(&self) -> usize {
+        self.body.as_deref().map_or(0, |body| body.lines().count())
+    }
+}
+
+/// Splits the lines following the header into the body and the trailing footers.
+fn split_footers<'a>(rest: &'a [&'a str]) -> (&'a [&'a str], Vec<Footer>) {
+    let paragraph = rest
+        .iter()
+        .rposition(|line| line.trim().is_empty())
+        .map_or(0, |i| i + 1);
+    if rest
+        .get(paragraph)
+        .and_then(|line| Footer::parse(line))
+        .is_none()
+    {
+        return (rest, Vec::new());
+    }
+    let mut footers: Vec<Footer> = Vec::new();
+    for line in &rest[paragraph..] {
+        match (Footer::parse(line), footers.last_mut()) {
+            (Some(footer), _) => footers.push(footer),
+            (None, Some(last)) => {
+                last.value.push('\n');
+                last.value.push_str(line);
+            }
+            (None, None) => {}
+        }
+    }
+    (&rest[..paragraph], footers)
+}
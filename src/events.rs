@@ -0,0 +1,145 @@
+use crate::hooks::Language;
+use crate::report::HookStatus;
+use serde::Serialize;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+///
+/// Something which happened while the hooks ran.
+///
+/// Events are serialized as JSON objects whose `event` key names the variant in kebab-case,
+/// such as `{"event":"hook-started","language":"Rust","id":"rust-fmt",...}`.
+///
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "event", rename_all = "kebab-case")]
+pub enum Event {
+    /// The run started for the detected languages.
+    RunStarted {
+        repository: Option<String>,
+        languages: Vec<Language>,
+    },
+    /// A project was detected and its hooks are about to run.
+    LanguageDetected {
+        language: Language,
+        root: PathBuf,
+        hooks: usize,
+    },
+    /// A hook started.
+    HookStarted {
+        language: Language,
+        root: PathBuf,
+        id: String,
+        description: String,
+    },
+    /// A hook finished.
+    HookFinished {
+        language: Language,
+        root: PathBuf,
+        id: String,
+        status: HookStatus,
+        exit_code: Option<i32>,
+        signal: Option<i32>,
+        duration_ms: u128,
+    },
+    /// Every hook of a project finished.
+    LanguageFinished {
+        language: Language,
+        root: PathBuf,
+        success: bool,
+        duration_ms: u128,
+    },
+    /// The run finished.
+    RunFinished {
+        success: bool,
+        passed: usize,
+        failed: usize,
+        warned: usize,
+        timed_out: usize,
+        skipped: usize,
+        cancelled: usize,
+        tool_missing: usize,
+        duration_ms: u128,
+    },
+}
+
+///
+/// Receives the events of a run as they happen.
+///
+/// Hooks of different projects run in parallel, so a sink must be shareable between
+/// threads.
+///
+pub trait EventSink: Sync {
+    /// Handles one event.
+    fn emit(&self, event: &Event);
+}
+
+///
+/// A sink ignoring every event.
+///
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoEvents;
+
+impl EventSink for NoEvents {
+    fn emit(&self, _event: &Event) {}
+}
+
+///
+/// A sink writing each event as one line of JSON, flushed as soon as it is written.
+///
+/// Write errors are ignored, so that a closed pipe does not interrupt the hooks.
+///
+/// # Example
+/// ```rust
+/// use breathes::events::{Event, EventSink, JsonLines};
+/// use breathes::hooks::Language;
+///
+/// let sink = JsonLines::new(Vec::new());
+/// sink.emit(&Event::LanguageDetected {
+///     language: Language::Rust,
+///     root: "backend".into(),
+///     hooks: 8,
+/// });
+/// let output = String::from_utf8(sink.into_inner()).unwrap();
+/// assert_eq!(
+///     output,
+///     "{\"event\":\"language-detected\",\"language\":\"Rust\",\"root\":\"backend\",\"hooks\":8}\n"
+/// );
+/// ```
+#[derive(Debug)]
+pub struct JsonLines<W: Write + Send> {
+    out: Mutex<W>,
+}
+
+impl<W: Write + Send> JsonLines<W> {
+    ///
+    /// Creates a sink writing to `out`.
+    ///
+    pub const fn new(out: W) -> Self {
+        Self {
+            out: Mutex::new(out),
+        }
+    }
+
+    ///
+    /// Returns the writer given to [`JsonLines::new`].
+    ///
+    pub fn into_inner(self) -> W {
+        self.out
+            .into_inner()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+impl<W: Write + Send> EventSink for JsonLines<W> {
+    fn emit(&self, event: &Event) {
+        let Ok(line) = serde_json::to_string(event) else {
+            return;
+        };
+        let mut out = self
+            .out
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let _ = writeln!(out, "{line}").and_then(|()| out.flush());
+    }
+}
@@ -0,0 +1,228 @@
+use crate::projects::is_top_level;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use ignore::WalkBuilder;
+use std::io::{Error, ErrorKind};
+use std::path::{Path, PathBuf};
+
+/// The placeholder replaced by the files to check in the command of a hook.
+pub const FILES_PLACEHOLDER: &str = "{files}";
+
+/// The longest command given to the shell, so that the file lists stay below the limits of
+/// the system (8191 characters for `cmd.exe`).
+#[cfg(windows)]
+pub const MAX_COMMAND_LENGTH: usize = 8000;
+
+/// The longest command given to the shell, so that the file lists stay below the limits of
+/// the system (128 KiB for a single argument on Linux).
+#[cfg(not(windows))]
+pub const MAX_COMMAND_LENGTH: usize = 100_000;
+
+///
+/// Builds the matcher of the `files` patterns of a hook.
+///
+/// A pattern without a `/`, such as `*.swift`, matches files in every directory.
+///
+/// # Errors
+/// - If a pattern is not a valid glob.
+///
+pub fn glob_set(patterns: &[impl AsRef<str>]) -> Result<GlobSet, Error> {
+    let mut set = GlobSetBuilder::new();
+    for pattern in patterns {
+        let pattern = pattern.as_ref();
+        let glob = Glob::new(pattern).map_err(|e| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("invalid pattern '{pattern}' in `files`: {e}"),
+            )
+        })?;
+        set.add(glob);
+    }
+    set.build()
+        .map_err(|e| Error::new(ErrorKind::InvalidData, e.to_string()))
+}
+
+///
+/// Returns the files a hook of the project at `root` may check, relative to `root`.
+///
+/// `root` is relative to `checkout`, the directory holding the files: `.` for the working
+/// tree, or a snapshot of the index. With `staged`, the staged files under `root` which
+/// still exist are kept. Otherwise, every file of the project is listed, hidden ones
+/// included, honoring `.gitignore`. In both cases, the files of the projects nested in
+/// `root`, among the `roots` of every project of the checkout, are left to them.
+///
+#[must_use]
+pub fn project_files(
+    checkout: &Path,
+    root: &Path,
+    roots: &[PathBuf],
+    staged: Option<&[PathBuf]>,
+) -> Vec<PathBuf> {
+    let dir = checkout.join(root);
+    let nested: Vec<PathBuf> = roots
+        .iter()
+        .filter(|other| other.as_path() != root && !is_top_level(other))
+        .filter_map(|other| {
+            if is_top_level(root) {
+                Some(other.clone())
+            } else {
+                other.strip_prefix(root).ok().map(Path::to_path_buf)
+            }
+        })
+        .collect();
+    let Some(staged) = staged else {
+        let top = dir.clone();
+        return WalkBuilder::new(&dir)
+            .require_git(false)
+            .hidden(false)
+            .sort_by_file_name(Ord::cmp)
+            .filter_entry(move |entry| {
+                entry.file_name() != ".git"
+                    && entry
+                        .path()
+                        .strip_prefix(&top)
+                        .map_or(true, |path| !nested.iter().any(|other| other == path))
+            })
+            .build()
+            .flatten()
+            .filter(|entry| {
+                entry
+                    .file_type()
+                    .is_some_and(|file_type| file_type.is_file())
+            })
+            .filter_map(|entry| entry.path().strip_prefix(&dir).ok().map(Path::to_path_buf))
+            .collect();
+    };
+    staged
+        .iter()
+        .filter_map(|file| {
+            let file = if is_top_level(root) {
+                file.as_path()
+            } else {
+                file.strip_prefix(root).ok()?
+            };
+            let owned = !nested.iter().any(|other| file.starts_with(other));
+            (owned && dir.join(file).is_file()).then(|| file.to_path_buf())
+        })
+        .collect()
+}
+
+///
+/// Replaces [`FILES_PLACEHOLDER`] in `command` with `files`, split across as many commands
+/// as needed to keep each of them under `max_length` characters.
+///
+/// Every file is quoted for the shell. A file too long to fit with the rest of the command
+/// still gets a command of its own. Without files, there is no command to run.
+///
+/// # Example
+/// ```rust
+/// use breathes::files::fill;
+/// use std::path::Path;
+///
+/// let files = [Path::new("a.swift"), Path::new("b.swift"), Path::new("my file.swift")];
+/// let commands = fill("swiftformat --lint {files}", &files, 40);
+/// # #[cfg(unix)]
+/// assert_eq!(
+///     commands,
+///     [
+///         "swiftformat --lint a.swift b.swift",
+///         "swiftformat --lint 'my file.swift'",
+///     ]
+/// );
+/// assert!(fill("swiftformat --lint {files}", &[], 40).is_empty());
+/// ```
+#[must_use]
+pub fn fill(command: &str, files: &[&Path], max_length: usize) -> Vec<String> {
+    let placeholders = command.matches(FILES_PLACEHOLDER).count().max(1);
+    let base = command
+        .len()
+        .saturating_sub(FILES_PLACEHOLDER.len() * placeholders);
+    let mut commands = Vec::new();
+    let mut chunk: Vec<String> = Vec::new();
+    let mut length = base;
+    for file in files {
+        let file = quote(file);
+        let added = (file.len() + 1) * placeholders;
+        if !chunk.is_empty() && length + added > max_length {
+            commands.push(command.replace(FILES_PLACEHOLDER, &chunk.join(" ")));
+            chunk.clear();
+            length = base;
+        }
+        length += added;
+        chunk.push(file);
+    }
+    if !chunk.is_empty() {
+        commands.push(command.replace(FILES_PLACEHOLDER, &chunk.join(" ")));
+    }
+    commands
+}
+
+///
+/// Quotes `file` for the shell running the hooks, if it contains special characters.
+///
+/// A file starting with `-` is prefixed with `./`, so that the tools do not take it for
+/// an option.
+///
+/// # Example
+/// ```rust
+/// use breathes::files::quote;
+/// use std::path::Path;
+///
+/// assert_eq!(quote(Path::new("src/main.rs")), "src/main.rs");
+/// assert_eq!(quote(Path::new("-v.rs")), "./-v.rs");
+/// # #[cfg(unix)]
+/// assert_eq!(quote(Path::new("it's here.md")), r"'it'\''s here.md'");
+/// ```
+#[must_use]
+pub fn quote(file: &Path) -> String {
+    let mut file = file.to_string_lossy();
+    if file.starts_with('-') {
+        file = format!("./{file}").into();
+    }
+    let safe = |c: char| c.is_ascii_alphanumeric() || "-_./+@%:,=".contains(c);
+    if !file.is_empty() && file.chars().all(safe) {
+        return file.into_owned();
+    }
+    if cfg!(windows) {
+        format!("\"{}\"", file.replace('"', "\"\""))
+    } else {
+        format!("'{}'", file.replace('\'', r"'\''"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::project_files;
+    use crate::testing::Fixture;
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn project_files_leave_nested_projects_out() {
+        let fixture = Fixture::new("project-files");
+        fixture
+            .write("Cargo.toml", "")
+            .write(".rustfmt.toml", "")
+            .write("src/main.rs", "")
+            .write("web/package.json", "{}")
+            .write("web/index.js", "");
+        let roots = [PathBuf::from("."), PathBuf::from("web")];
+
+        assert_eq!(
+            project_files(fixture.path(), Path::new("."), &roots, None),
+            [
+                PathBuf::from(".rustfmt.toml"),
+                PathBuf::from("Cargo.toml"),
+                PathBuf::from("src/main.rs"),
+            ]
+        );
+        assert_eq!(
+            project_files(fixture.path(), Path::new("web"), &roots, None),
+            [PathBuf::from("index.js"), PathBuf::from("package.json")]
+        );
+
+        let staged = [PathBuf::from("src/main.rs"), PathBuf::from("web/index.js")];
+        assert_eq!(
+            project_files(fixture.path(), Path::new("."), &roots, Some(&staged)),
+            [PathBuf::from("src/main.rs")]
+        );
+    }
+}
@@ -1,13 +1,28 @@
+use crate::config::{BuiltinHooks, Config, HookConfig};
+use crate::events::{Event, EventSink, NoEvents};
+use crate::files::{FILES_PLACEHOLDER, MAX_COMMAND_LENGTH, fill, glob_set, project_files};
+use crate::node::{Package, PackageManager};
+use crate::process::{Waited, spawn_group, wait_or_kill};
+use crate::projects::{Project, discover, is_top_level, staged_files, touched};
+use crate::python::Toolchain;
+use crate::report::{HookReport, HookStatus, LanguageReport, RunReport};
+use crate::scheduler::{Jobs, schedule};
+use crate::snapshot::Snapshot;
+use crate::tools::{MissingTool, missing_tool};
 use crossterm::style::Stylize;
 use glob::glob;
-use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle};
 use rayon::prelude::*;
+use serde::{Deserialize, Serialize, Serializer};
+use std::borrow::Cow;
+use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
 use std::fs::{File, create_dir_all};
-use std::io::Error;
+use std::io::{Error, ErrorKind};
 use std::path::{Path, PathBuf};
-use std::process::Command;
-use std::time::Instant;
+use std::process::{Command, ExitStatus, Stdio};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
 
 /// A constant string representing the file extension pattern for C# project files.
 ///
@@ -352,6 +367,20 @@ pub const HASKELL_FILE: &str = "*.cabal";
 ///
 pub const D_FILE: &str = "dub.json";
 
+/// A constant representing the name of the metadata file of an R package.
+///
+/// # Description
+/// Every R package holds a `DESCRIPTION` file declaring its name, version and
+/// dependencies, which `R CMD check` reads before checking the package.
+///
+/// # Example
+/// ```rust
+/// use breathes::hooks::R_FILE;
+/// assert_eq!(R_FILE, "DESCRIPTION");
+/// ```
+///
+pub const R_FILE: &str = "DESCRIPTION";
+
 ///
 /// An enumeration representing various programming languages.
 ///
@@ -383,8 +412,9 @@ pub const D_FILE: &str = "dub.json";
 /// * `Swift`: Represents the Swift programming language.
 /// * `Dart`: Represents the Dart programming language.
 /// * `Elixir`: Represents the Elixir programming language.
-#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Default, Hash, Eq, PartialEq)]
 pub enum Language {
+    #[default]
     Unknown,
     R,
     Javascript,
@@ -459,11 +489,77 @@ impl From<String> for Language {
         if value.eq("Haskell") {
             return Self::Haskell;
         }
+        if value.eq("R") {
+            return Self::R;
+        }
         Self::Unknown
     }
 }
 
+impl FromStr for Language {
+    type Err = Error;
+
+    ///
+    /// Parses a language name case-insensitively, as written in `breathes.toml`.
+    ///
+    /// Unlike `From<String>`, an unrecognized name is an error instead of `Language::Unknown`.
+    ///
+    /// ```rust
+    /// use breathes::hooks::Language;
+    /// assert_eq!("rust".parse::<Language>().unwrap(), Language::Rust);
+    /// assert!("cobol".parse::<Language>().is_err());
+    /// ```
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "r" => Ok(Self::R),
+            "javascript" => Ok(Self::Javascript),
+            "typescript" => Ok(Self::Typescript),
+            "haskell" => Ok(Self::Haskell),
+            "d" => Ok(Self::D),
+            "rust" => Ok(Self::Rust),
+            "python" => Ok(Self::Python),
+            "go" => Ok(Self::Go),
+            "php" => Ok(Self::Php),
+            "ruby" => Ok(Self::Ruby),
+            "cmake" => Ok(Self::CMake),
+            "csharp" => Ok(Self::CSharp),
+            "maven" => Ok(Self::Maven),
+            "kotlin" => Ok(Self::Kotlin),
+            "gradle" => Ok(Self::Gradle),
+            "swift" => Ok(Self::Swift),
+            "dart" => Ok(Self::Dart),
+            "elixir" => Ok(Self::Elixir),
+            _ => Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("unknown language '{value}'"),
+            )),
+        }
+    }
+}
+
 impl Language {
+    /// Every language known by breathes, except `Language::Unknown`.
+    pub const ALL: [Self; 18] = [
+        Self::R,
+        Self::Javascript,
+        Self::Typescript,
+        Self::Haskell,
+        Self::D,
+        Self::Rust,
+        Self::Python,
+        Self::Go,
+        Self::Php,
+        Self::Ruby,
+        Self::CMake,
+        Self::CSharp,
+        Self::Maven,
+        Self::Kotlin,
+        Self::Gradle,
+        Self::Swift,
+        Self::Dart,
+        Self::Elixir,
+    ];
+
     #[must_use]
     pub const fn get_file(language: Self) -> &'static str {
         match language {
@@ -484,7 +580,8 @@ impl Language {
             Self::Dart => DART_FILE,
             Self::Elixir => ELIXIR_FILE,
             Self::D => D_FILE,
-            Self::R | Self::Unknown => "",
+            Self::R => R_FILE,
+            Self::Unknown => "",
         }
     }
 }
@@ -525,15 +622,22 @@ impl Language {
 /// - `Language::CMake` => `CMAKE_FILE`
 /// - `Language::Elixir` => `ELIXIR_FILE`
 /// - `Language::Python` => `PYTHON_FILE`
+/// - `Language::D` => `D_FILE`
+/// - `Language::R` => `R_FILE`
 ///
 /// # Notes
 ///
 /// This array can be used for operations such as:
 /// - Determining file types for code generation.
 /// - Associating files to specific programming languages in a codebase.
+///
 /// Ensure to update this constant if new languages or file types are added to
 /// the `Language` enum in the future.
-pub const LANGUAGES: [(Language, &str); 16] = [
+///
+/// Only the primary manifest of each language is listed here: the projects are detected
+/// from every manifest, lockfile and source file listed in
+/// [`SIGNALS`](crate::projects::SIGNALS).
+pub const LANGUAGES: [(Language, &str); 18] = [
     (Language::Rust, RUST_FILE),
     (Language::Typescript, TYPESCRIPT_FILE),
     (Language::Haskell, HASKELL_FILE),
@@ -550,7 +654,15 @@ pub const LANGUAGES: [(Language, &str); 16] = [
     (Language::CMake, CMAKE_FILE),
     (Language::Elixir, ELIXIR_FILE),
     (Language::Python, PYTHON_FILE),
+    (Language::D, D_FILE),
+    (Language::R, R_FILE),
 ];
+impl Serialize for Language {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
 impl Display for Language {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -576,133 +688,277 @@ impl Display for Language {
         }
     }
 }
-#[derive(Clone)]
+///
+/// A verification command run for a language.
+///
+/// Built-in hooks borrow their text from static strings, while hooks declared in
+/// `breathes.toml` own theirs, hence the [`Cow`] fields.
+///
+/// # Fields
+/// * `language`: The language this hook belongs to.
+/// * `id`: A stable identifier, used to override the hook in `breathes.toml` (e.g. `rust-outdated`).
+/// * `description`: The message displayed while the hook runs.
+/// * `success`: The message describing a successful run.
+/// * `failure`: The message describing a failed run.
+/// * `file`: The name of the log files written under `breathes/<language>/`.
+/// * `command`: The shell command to execute.
+/// * `warn_only`: When set, a failure is reported but does not fail the run.
+/// * `output`: The format of the standard output of the command, read by the SARIF exporter.
+/// * `timeout`: The time after which the command and every process it started are killed.
+/// * `depends_on`: The ids of the hooks which must pass before this one starts.
+/// * `parallel`: Whether the hook may run alongside other hooks (`true` by default).
+/// * `files`: The glob patterns selecting the files given to a command using `{files}`
+///   (every file when empty).
+#[derive(Clone, Debug)]
 pub struct Hook {
     pub language: Language,
-    pub description: &'static str,
-    pub success: &'static str,
-    pub failure: &'static str,
-    pub file: &'static str,
-    pub command: &'static str,
+    pub id: Cow<'static, str>,
+    pub description: Cow<'static, str>,
+    pub success: Cow<'static, str>,
+    pub failure: Cow<'static, str>,
+    pub file: Cow<'static, str>,
+    pub command: Cow<'static, str>,
+    pub warn_only: bool,
+    pub output: OutputFormat,
+    pub timeout: Option<Duration>,
+    pub depends_on: Vec<Cow<'static, str>>,
+    pub parallel: bool,
+    pub files: Vec<Cow<'static, str>>,
+}
+
+impl Default for Hook {
+    fn default() -> Self {
+        Self {
+            language: Language::default(),
+            id: Cow::default(),
+            description: Cow::default(),
+            success: Cow::default(),
+            failure: Cow::default(),
+            file: Cow::default(),
+            command: Cow::default(),
+            warn_only: false,
+            output: OutputFormat::default(),
+            timeout: None,
+            depends_on: Vec::new(),
+            parallel: true,
+            files: Vec::new(),
+        }
+    }
+}
+
+///
+/// The format of the standard output of a hook.
+///
+/// Hooks writing machine-readable findings let [`crate::sarif`] locate each finding in the
+/// source code.
+///
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum OutputFormat {
+    /// Free text, without findings to extract.
+    #[default]
+    Text,
+    /// The JSON messages of `cargo --message-format=json` (`cargo check`, `cargo clippy`, ...).
+    CargoJson,
+    /// The `file(line,col): error TSxxxx: message` lines of `tsc`.
+    Tsc,
 }
 
 impl Hook {
     pub fn d(hooks: &mut Vec<Self>) {
         hooks.push(Self {
             language: Language::D,
-            description: "Building your project",
-            success: "Build successful",
-            failure: "Build failed",
-            file: "build.log",
-            command: "dub build",
+            id: "d-build".into(),
+            description: "Building your project".into(),
+            success: "Build successful".into(),
+            failure: "Build failed".into(),
+            file: "build.log".into(),
+            command: "dub build".into(),
+            ..Self::default()
         });
         hooks.push(Self {
             language: Language::D,
-            description: "Testing your project",
-            success: "Tests passed",
-            failure: "Tests failed",
-            file: "test.log",
-            command: "dub test",
+            id: "d-test".into(),
+            description: "Testing your project".into(),
+            success: "Tests passed".into(),
+            failure: "Tests failed".into(),
+            file: "test.log".into(),
+            command: "dub test".into(),
+            depends_on: vec!["d-build".into()],
+            ..Self::default()
+        });
+    }
+
+    pub fn r(hooks: &mut Vec<Self>) {
+        hooks.push(Self {
+            language: Language::R,
+            id: "r-check".into(),
+            description: "Checking your package".into(),
+            success: "Package check passed".into(),
+            failure: "Package check failed".into(),
+            file: "check.log".into(),
+            command:
+                "Rscript -e \"rcmdcheck::rcmdcheck(args = '--no-manual', error_on = 'warning')\""
+                    .into(),
+            // The check builds and installs the package, which the other hooks also load.
+            parallel: false,
+            ..Self::default()
+        });
+        hooks.push(Self {
+            language: Language::R,
+            id: "r-test".into(),
+            description: "Testing your project".into(),
+            success: "Tests passed".into(),
+            failure: "Tests failed".into(),
+            file: "test.log".into(),
+            command: "Rscript -e \"testthat::test_local(stop_on_failure = TRUE)\"".into(),
+            ..Self::default()
+        });
+        hooks.push(Self {
+            language: Language::R,
+            id: "r-lint".into(),
+            description: "Linting your code".into(),
+            success: "No lints found".into(),
+            failure: "Lints found".into(),
+            file: "lint.log".into(),
+            command: "Rscript -e \"lints <- lintr::lint_dir(); print(lints); quit(status = as.integer(length(lints) > 0))\"".into(),
+            ..Self::default()
+        });
+        hooks.push(Self {
+            language: Language::R,
+            id: "r-style".into(),
+            description: "Checking for code formatting".into(),
+            success: "Code formatting is correct".into(),
+            failure: "Code formatting issues found".into(),
+            file: "style.log".into(),
+            command: "Rscript -e \"styler::style_dir(dry = 'fail')\"".into(),
+            ..Self::default()
         });
     }
 
     pub fn haskell(hooks: &mut Vec<Self>) {
         hooks.push(Self {
             language: Language::Haskell,
-            description: "Checking for outdated packages in your project",
-            success: "No outdated packages found",
-            failure: "Outdated packages found",
-            file: "outdated.log",
-            command: "cabal outdated",
+            id: "haskell-outdated".into(),
+            description: "Checking for outdated packages in your project".into(),
+            success: "No outdated packages found".into(),
+            failure: "Outdated packages found".into(),
+            file: "outdated.log".into(),
+            command: "cabal outdated".into(),
+            ..Self::default()
         });
         hooks.push(Self {
             language: Language::Haskell,
-            description: "Running tests for your Haskell project",
-            success: "Tests passed",
-            failure: "Tests failed",
-            file: "test.log",
-            command: "cabal test",
+            id: "haskell-test".into(),
+            description: "Running tests for your Haskell project".into(),
+            success: "Tests passed".into(),
+            failure: "Tests failed".into(),
+            file: "test.log".into(),
+            command: "cabal test".into(),
+            ..Self::default()
         });
     }
     pub fn typescript(hooks: &mut Vec<Self>) {
         Self::javascript(hooks);
         hooks.push(Self {
             language: Language::Typescript,
-            description: "Checking for types",
-            success: "Types are valid",
-            failure: "Type errors found",
-            file: "types.log",
-            command: "npx tsc --noEmit",
+            id: "typescript-types".into(),
+            description: "Checking for types".into(),
+            success: "Types are valid".into(),
+            failure: "Type errors found".into(),
+            file: "types.log".into(),
+            command: "npx tsc --noEmit".into(),
+            output: OutputFormat::Tsc,
+            ..Self::default()
         });
         hooks.push(Self {
             language: Language::Typescript,
-            description: "Checking for code formatting in your project",
-            success: "Code is formatted correctly",
-            failure: "Code formatting issues found",
-            file: "fmt.log",
-            command: "npx prettier --check .",
+            id: "typescript-fmt".into(),
+            description: "Checking for code formatting in your project".into(),
+            success: "Code is formatted correctly".into(),
+            failure: "Code formatting issues found".into(),
+            file: "fmt.log".into(),
+            command: "npx prettier --check --ignore-unknown {files}".into(),
+            files: vec!["*.{ts,tsx,js,jsx,mjs,cjs,json,css,scss,md,yml,yaml,html}".into()],
+            ..Self::default()
         });
     }
     pub fn maven(hooks: &mut Vec<Self>) {
         hooks.push(Self {
             language: Language::Maven,
-            description: "Checking for security vulnerabilities",
-            success: "No vulnerabilities found",
-            failure: "Vulnerabilities found",
-            file: "audit.log",
-            command: "mvn dependency-check:check",
+            id: "maven-audit".into(),
+            description: "Checking for security vulnerabilities".into(),
+            success: "No vulnerabilities found".into(),
+            failure: "Vulnerabilities found".into(),
+            file: "audit.log".into(),
+            command: "mvn dependency-check:check".into(),
+            ..Self::default()
         });
         hooks.push(Self {
             language: Language::Maven,
-            description: "Running tests for your Maven project",
-            success: "Tests passed",
-            failure: "Tests failed",
-            file: "test.log",
-            command: "mvn test",
+            id: "maven-test".into(),
+            description: "Running tests for your Maven project".into(),
+            success: "Tests passed".into(),
+            failure: "Tests failed".into(),
+            file: "test.log".into(),
+            command: "mvn test".into(),
+            ..Self::default()
         });
         hooks.push(Self {
             language: Language::Maven,
-            description: "Checking for outdated packages in your project",
-            success: "No outdated packages found",
-            failure: "Outdated packages found",
-            file: "outdated.log",
-            command: "mvn versions:display-dependency-updates",
+            id: "maven-outdated".into(),
+            description: "Checking for outdated packages in your project".into(),
+            success: "No outdated packages found".into(),
+            failure: "Outdated packages found".into(),
+            file: "outdated.log".into(),
+            command: "mvn versions:display-dependency-updates".into(),
+            ..Self::default()
         });
     }
     pub fn gradle(hooks: &mut Vec<Self>) {
         if cfg!(target_os = "windows") {
             hooks.push(Self {
                 language: Language::Gradle,
-                description: "Building your application",
-                success: "Build successful",
-                failure: "Build failed",
-                file: "build.log",
-                command: "gradlew.bat build",
+                id: "gradle-build".into(),
+                description: "Building your application".into(),
+                success: "Build successful".into(),
+                failure: "Build failed".into(),
+                file: "build.log".into(),
+                command: "gradlew.bat build".into(),
+                ..Self::default()
             });
             hooks.push(Self {
                 language: Language::Gradle,
-                description: "Running unit test",
-                success: "Test passed",
-                failure: "Test failed",
-                file: "test.log",
-                command: "gradlew.bat test",
+                id: "gradle-test".into(),
+                description: "Running unit test".into(),
+                success: "Test passed".into(),
+                failure: "Test failed".into(),
+                file: "test.log".into(),
+                command: "gradlew.bat test".into(),
+                depends_on: vec!["gradle-build".into()],
+                ..Self::default()
             });
         } else {
             hooks.push(Self {
                 language: Language::Gradle,
-                description: "Building your application",
-                success: "Build successful",
-                failure: "Build failed",
-                file: "build.log",
-                command: "gradlew build",
+                id: "gradle-build".into(),
+                description: "Building your application".into(),
+                success: "Build successful".into(),
+                failure: "Build failed".into(),
+                file: "build.log".into(),
+                command: "gradlew build".into(),
+                ..Self::default()
             });
             hooks.push(Self {
                 language: Language::Gradle,
-                description: "Running unit test",
-                success: "Test passed",
-                failure: "Test failed",
-                file: "test.log",
-                command: "gradlew test",
+                id: "gradle-test".into(),
+                description: "Running unit test".into(),
+                success: "Test passed".into(),
+                failure: "Test failed".into(),
+                file: "test.log".into(),
+                command: "gradlew test".into(),
+                depends_on: vec!["gradle-build".into()],
+                ..Self::default()
             });
         }
     }
@@ -710,403 +966,721 @@ impl Hook {
     pub fn javascript(hooks: &mut Vec<Self>) {
         hooks.push(Self {
             language: Language::Javascript,
-            description: "Checking for outdated packages in your project",
-            success: "No outdated packages found",
-            failure: "Outdated packages found",
-            file: "outdated.log",
-            command: "npm outdated",
+            id: "javascript-outdated".into(),
+            description: "Checking for outdated packages in your project".into(),
+            success: "No outdated packages found".into(),
+            failure: "Outdated packages found".into(),
+            file: "outdated.log".into(),
+            command: "npm outdated".into(),
+            ..Self::default()
         });
         hooks.push(Self {
             language: Language::Javascript,
-            description: "Testing your project",
-            success: "Tests passed",
-            failure: "Tests failed",
-            file: "test.log",
-            command: "npm run test",
+            id: "javascript-test".into(),
+            description: "Testing your project".into(),
+            success: "Tests passed".into(),
+            failure: "Tests failed".into(),
+            file: "test.log".into(),
+            command: "npm run test".into(),
+            ..Self::default()
         });
         hooks.push(Self {
             language: Language::Javascript,
-            description: "Auditing your project",
-            success: "No vulnerabilities found",
-            failure: "Vulnerabilities found",
-            file: "audit.log",
-            command: "npm audit",
+            id: "javascript-audit".into(),
+            description: "Auditing your project".into(),
+            success: "No vulnerabilities found".into(),
+            failure: "Vulnerabilities found".into(),
+            file: "audit.log".into(),
+            command: "npm audit".into(),
+            ..Self::default()
         });
         hooks.push(Self {
             language: Language::Javascript,
-            description: "Checking for code formatting in your project",
-            success: "Linting passed",
-            failure: "Lint error found",
-            file: "lint.log",
-            command: "npm run lint",
+            id: "javascript-lint".into(),
+            description: "Checking for code formatting in your project".into(),
+            success: "Linting passed".into(),
+            failure: "Lint error found".into(),
+            file: "lint.log".into(),
+            command: "npm run lint".into(),
+            ..Self::default()
         });
     }
     pub fn rust(hooks: &mut Vec<Self>) {
         hooks.push(Self {
             language: Language::Rust,
-            description: "Checking the configuration",
-            success: "Project is valid",
-            failure: "Project not valid",
-            file: "project.log",
-            command: "cargo verify-project",
+            id: "rust-project".into(),
+            description: "Checking the configuration".into(),
+            success: "Project is valid".into(),
+            failure: "Project not valid".into(),
+            file: "project.log".into(),
+            command: "cargo verify-project".into(),
+            ..Self::default()
         });
         hooks.push(Self {
             language: Language::Rust,
-            description: "Checking build capability",
-            success: "Can build the project",
-            failure: "Cargo check detect failure",
-            file: "check.log",
-            command: "cargo check",
+            id: "rust-check".into(),
+            description: "Checking build capability".into(),
+            success: "Can build the project".into(),
+            failure: "Cargo check detect failure".into(),
+            file: "check.log".into(),
+            command: "cargo check".into(),
+            ..Self::default()
         });
         hooks.push(Self {
             language: Language::Rust,
-            description: "Checking for security vulnerabilities",
-            success: "No vulnerabilities found",
-            failure: "Vulnerabilities found",
-            file: "audit.log",
-            command: "cargo audit",
+            id: "rust-audit".into(),
+            description: "Checking for security vulnerabilities".into(),
+            success: "No vulnerabilities found".into(),
+            failure: "Vulnerabilities found".into(),
+            file: "audit.log".into(),
+            command: "cargo audit".into(),
+            ..Self::default()
         });
         hooks.push(Self {
             language: Language::Rust,
-            description: "Checks for formatting issues in your Rust code",
-            file: "fmt.log",
-            success: "Code format standard respected",
-            failure: "Code format standard not respected",
-            command: "cargo fmt --check",
+            id: "rust-fmt".into(),
+            description: "Checks for formatting issues in your Rust code".into(),
+            file: "fmt.log".into(),
+            success: "Code format standard respected".into(),
+            failure: "Code format standard not respected".into(),
+            command: "rustfmt --check --edition 2024 {files}".into(),
+            files: vec!["*.rs".into()],
+            ..Self::default()
         });
         hooks.push(Self {
             language: Language::Rust,
-            description: "Checks for linting issues and suggests code improvements",
-            success: "No warnings found",
-            failure: "Warnings found",
-            file: "clippy.log",
-            command: "cargo clippy -- -D clippy::all -W warnings -D clippy::pedantic -D clippy::nursery -A clippy::multiple_crate_versions",
+            id: "rust-clippy".into(),
+            description: "Checks for linting issues and suggests code improvements".into(),
+            success: "No warnings found".into(),
+            failure: "Warnings found".into(),
+            file: "clippy.log".into(),
+            command: "cargo clippy -- -D clippy::all -W warnings -D clippy::pedantic -D clippy::nursery -A clippy::multiple_crate_versions".into(),
+            output: OutputFormat::CargoJson,
+            depends_on: vec!["rust-check".into()],
+            ..Self::default()
         });
         hooks.push(Self {
             language: Language::Rust,
-            description: "Testing your project",
-            success: "Tests passed",
-            failure: "Tests failed",
-            file: "test.log",
-            command: "cargo test --no-fail-fast",
+            id: "rust-test".into(),
+            description: "Testing your project".into(),
+            success: "Tests passed".into(),
+            failure: "Tests failed".into(),
+            file: "test.log".into(),
+            command: "cargo test --no-fail-fast".into(),
+            depends_on: vec!["rust-check".into()],
+            ..Self::default()
         });
         hooks.push(Self {
             language: Language::Rust,
-            description: "Generating documentation for your project",
-            success: "Documentation generated",
-            failure: "Failed to generate documentation",
-            file: "doc.log",
-            command: "cargo doc --no-deps --document-private-items",
+            id: "rust-doc".into(),
+            description: "Generating documentation for your project".into(),
+            success: "Documentation generated".into(),
+            failure: "Failed to generate documentation".into(),
+            file: "doc.log".into(),
+            command: "cargo doc --no-deps --document-private-items".into(),
+            depends_on: vec!["rust-check".into()],
+            ..Self::default()
         });
         hooks.push(Self {
             language: Language::Rust,
-            description: "Checking for outdated packages in your project",
-            success: "No outdated packages found",
-            failure: "Outdated packages found",
-            file: "outdated.log",
-            command: "cargo outdated",
+            id: "rust-outdated".into(),
+            description: "Checking for outdated packages in your project".into(),
+            success: "No outdated packages found".into(),
+            failure: "Outdated packages found".into(),
+            file: "outdated.log".into(),
+            command: "cargo outdated".into(),
+            ..Self::default()
         });
     }
 
     pub fn python(hooks: &mut Vec<Self>) {
         hooks.push(Self {
             language: Language::Python,
-            description: "Checking for outdated packages in your project",
-            success: "No outdated packages found",
-            failure: "Outdated packages found",
-            file: "outdated.log",
-            command: "pip list --outdated",
+            id: "python-outdated".into(),
+            description: "Checking for outdated packages in your project".into(),
+            success: "No outdated packages found".into(),
+            failure: "Outdated packages found".into(),
+            file: "outdated.log".into(),
+            command: "pip list --outdated".into(),
+            ..Self::default()
+        });
+        hooks.push(Self {
+            language: Language::Python,
+            id: "python-audit".into(),
+            description: "Checking for security vulnerabilities".into(),
+            success: "No vulnerabilities found".into(),
+            failure: "Vulnerabilities found".into(),
+            file: "audit.log".into(),
+            command: "pip-audit".into(),
+            ..Self::default()
+        });
+        hooks.push(Self {
+            language: Language::Python,
+            id: "python-test".into(),
+            description: "Testing your project".into(),
+            success: "Tests passed".into(),
+            failure: "Tests failed".into(),
+            file: "test.log".into(),
+            command: "pytest".into(),
+            ..Self::default()
+        });
+        hooks.push(Self {
+            language: Language::Python,
+            id: "python-lint".into(),
+            description: "Linting your code".into(),
+            success: "No lint errors found".into(),
+            failure: "Lint errors found".into(),
+            file: "lint.log".into(),
+            command: "ruff check".into(),
+            ..Self::default()
         });
         hooks.push(Self {
             language: Language::Python,
-            description: "Checking for security vulnerabilities",
-            success: "No vulnerabilities found",
-            failure: "Vulnerabilities found",
-            file: "audit.log",
-            command: "pip audit",
+            id: "python-format".into(),
+            description: "Checking for code formatting".into(),
+            success: "Code formatting is correct".into(),
+            failure: "Code formatting issues found".into(),
+            file: "format.log".into(),
+            command: "ruff format --check".into(),
+            ..Self::default()
+        });
+        hooks.push(Self {
+            language: Language::Python,
+            id: "python-types".into(),
+            description: "Checking for types".into(),
+            success: "Types are valid".into(),
+            failure: "Type errors found".into(),
+            file: "types.log".into(),
+            command: "mypy .".into(),
+            ..Self::default()
         });
     }
     pub fn go(hooks: &mut Vec<Self>) {
         hooks.push(Self {
             language: Language::Go,
-            description: "Testing your project",
-            success: "Tests passed",
-            failure: "Tests failed",
-            file: "test.log",
-            command: "go test -v",
+            id: "go-test".into(),
+            description: "Testing your project".into(),
+            success: "Tests passed".into(),
+            failure: "Tests failed".into(),
+            file: "test.log".into(),
+            command: "go test -v".into(),
+            ..Self::default()
         });
         hooks.push(Self {
             language: Language::Go,
-            description: "Checking for security vulnerabilities",
-            success: "No vulnerabilities found",
-            failure: "Vulnerabilities found",
-            file: "audit.log",
-            command: "go list -u -m -json all",
+            id: "go-audit".into(),
+            description: "Checking for security vulnerabilities".into(),
+            success: "No vulnerabilities found".into(),
+            failure: "Vulnerabilities found".into(),
+            file: "audit.log".into(),
+            command: "go list -u -m -json all".into(),
+            ..Self::default()
         });
     }
     pub fn php(hooks: &mut Vec<Self>) {
         hooks.push(Self {
             language: Language::Php,
-            description: "Checking platform requirements",
-            success: "All requirements are met",
-            failure: "Missing requirements found",
-            file: "reqs.log",
-            command: "composer check-platform-reqs",
+            id: "php-reqs".into(),
+            description: "Checking platform requirements".into(),
+            success: "All requirements are met".into(),
+            failure: "Missing requirements found".into(),
+            file: "reqs.log".into(),
+            command: "composer check-platform-reqs".into(),
+            ..Self::default()
         });
         hooks.push(Self {
             language: Language::Php,
-            description: "Checking for security vulnerabilities",
-            success: "No vulnerabilities found",
-            failure: "Vulnerabilities found",
-            file: "audit.log",
-            command: "composer audit",
+            id: "php-audit".into(),
+            description: "Checking for security vulnerabilities".into(),
+            success: "No vulnerabilities found".into(),
+            failure: "Vulnerabilities found".into(),
+            file: "audit.log".into(),
+            command: "composer audit".into(),
+            ..Self::default()
         });
         hooks.push(Self {
             language: Language::Php,
-            description: "Checking outdated packages",
-            success: "No outdated packages found",
-            failure: "Outdated packages found",
-            file: "outdated.log",
-            command: "composer outdated",
+            id: "php-outdated".into(),
+            description: "Checking outdated packages".into(),
+            success: "No outdated packages found".into(),
+            failure: "Outdated packages found".into(),
+            file: "outdated.log".into(),
+            command: "composer outdated".into(),
+            ..Self::default()
         });
         hooks.push(Self {
             language: Language::Php,
-            description: "Running tests for your PHP project",
-            success: "Tests passed",
-            failure: "Tests failed",
-            file: "test.log",
-            command: "composer run test",
+            id: "php-test".into(),
+            description: "Running tests for your PHP project".into(),
+            success: "Tests passed".into(),
+            failure: "Tests failed".into(),
+            file: "test.log".into(),
+            command: "composer run test".into(),
+            ..Self::default()
         });
     }
 
     pub fn ruby(hooks: &mut Vec<Self>) {
         hooks.push(Self {
             language: Language::Ruby,
-            description: "Checking for outdated gems",
-            success: "No outdated gems found",
-            failure: "Outdated gems found",
-            file: "outdated.log",
-            command: "bundle outdated",
+            id: "ruby-outdated".into(),
+            description: "Checking for outdated gems".into(),
+            success: "No outdated gems found".into(),
+            failure: "Outdated gems found".into(),
+            file: "outdated.log".into(),
+            command: "bundle outdated".into(),
+            ..Self::default()
         });
         hooks.push(Self {
             language: Language::Ruby,
-            description: "Checking for security vulnerabilities",
-            success: "No vulnerabilities found",
-            failure: "Vulnerabilities found",
-            file: "audit.log",
-            command: "bundle audit",
+            id: "ruby-audit".into(),
+            description: "Checking for security vulnerabilities".into(),
+            success: "No vulnerabilities found".into(),
+            failure: "Vulnerabilities found".into(),
+            file: "audit.log".into(),
+            command: "bundle audit".into(),
+            ..Self::default()
         });
         hooks.push(Self {
             language: Language::Ruby,
-            description: "Running tests for your Ruby project",
-            success: "Tests passed",
-            failure: "Tests failed",
-            file: "test.log",
-            command: "bundle exec rspec",
+            id: "ruby-test".into(),
+            description: "Running tests for your Ruby project".into(),
+            success: "Tests passed".into(),
+            failure: "Tests failed".into(),
+            file: "test.log".into(),
+            command: "bundle exec rspec".into(),
+            ..Self::default()
         });
     }
     pub fn cmake(hooks: &mut Vec<Self>) {
         hooks.push(Self {
             language: Language::CMake,
-            description: "Generating build configuration",
-            success: "Configuration generated successfully",
-            failure: "Configuration failed",
-            file: "cmake.log",
-            command: "cmake -S . -B build",
+            id: "cmake-cmake".into(),
+            description: "Generating build configuration".into(),
+            success: "Configuration generated successfully".into(),
+            failure: "Configuration failed".into(),
+            file: "cmake.log".into(),
+            command: "cmake -S . -B build".into(),
+            ..Self::default()
         });
         hooks.push(Self {
             language: Language::CMake,
-            description: "Compiling the project",
-            success: "Build successful",
-            failure: "Build failed",
-            file: "build.log",
-            command: "cmake --build build",
+            id: "cmake-build".into(),
+            description: "Compiling the project".into(),
+            success: "Build successful".into(),
+            failure: "Build failed".into(),
+            file: "build.log".into(),
+            command: "cmake --build build".into(),
+            depends_on: vec!["cmake-cmake".into()],
+            ..Self::default()
         });
         hooks.push(Self {
             language: Language::CMake,
-            description: "Running tests",
-            success: "All tests passed",
-            failure: "Tests failed",
-            file: "test.log",
-            command: "ctest --test-dir build --output-on-failure",
+            id: "cmake-test".into(),
+            description: "Running tests".into(),
+            success: "All tests passed".into(),
+            failure: "Tests failed".into(),
+            file: "test.log".into(),
+            command: "ctest --test-dir build --output-on-failure".into(),
+            depends_on: vec!["cmake-build".into()],
+            ..Self::default()
         });
     }
     pub fn csharp(hooks: &mut Vec<Self>) {
         hooks.push(Self {
             language: Language::CSharp,
-            description: "Checking for code formatting",
-            success: "Code formatting is correct",
-            failure: "Code formatting issues found",
-            file: "format.log",
-            command: "dotnet format --verify-no-changes",
+            id: "csharp-format".into(),
+            description: "Checking for code formatting".into(),
+            success: "Code formatting is correct".into(),
+            failure: "Code formatting issues found".into(),
+            file: "format.log".into(),
+            command: "dotnet format --verify-no-changes".into(),
+            depends_on: vec!["csharp-deps".into()],
+            ..Self::default()
         });
         hooks.push(Self {
             language: Language::CSharp,
-            description: "Running unit tests",
-            success: "All tests passed",
-            failure: "Some tests failed",
-            file: "test.log",
-            command: "dotnet test",
+            id: "csharp-test".into(),
+            description: "Running unit tests".into(),
+            success: "All tests passed".into(),
+            failure: "Some tests failed".into(),
+            file: "test.log".into(),
+            command: "dotnet test".into(),
+            depends_on: vec!["csharp-build".into()],
+            ..Self::default()
         });
         hooks.push(Self {
             language: Language::CSharp,
-            description: "Building the project",
-            success: "Build successful",
-            failure: "Build failed",
-            file: "build.log",
-            command: "dotnet build",
+            id: "csharp-build".into(),
+            description: "Building the project".into(),
+            success: "Build successful".into(),
+            failure: "Build failed".into(),
+            file: "build.log".into(),
+            command: "dotnet build".into(),
+            depends_on: vec!["csharp-deps".into()],
+            ..Self::default()
         });
         hooks.push(Self {
             language: Language::CSharp,
-            description: "Checking for dependency updates",
-            success: "Dependencies are up to date",
-            failure: "Dependency updates available",
-            file: "deps.log",
-            command: "dotnet restore",
+            id: "csharp-deps".into(),
+            description: "Checking for dependency updates".into(),
+            success: "Dependencies are up to date".into(),
+            failure: "Dependency updates available".into(),
+            file: "deps.log".into(),
+            command: "dotnet restore".into(),
+            ..Self::default()
         });
         hooks.push(Self {
             language: Language::CSharp,
-            description: "Checking for security vulnerabilities",
-            success: "No vulnerabilities found",
-            failure: "Vulnerabilities found",
-            file: "audit.log",
-            command: "dotnet audit",
+            id: "csharp-audit".into(),
+            description: "Checking for security vulnerabilities".into(),
+            success: "No vulnerabilities found".into(),
+            failure: "Vulnerabilities found".into(),
+            file: "audit.log".into(),
+            command: "dotnet audit".into(),
+            depends_on: vec!["csharp-deps".into()],
+            ..Self::default()
         });
     }
 
     pub fn swift(hooks: &mut Vec<Self>) {
         hooks.push(Self {
             language: Language::Swift,
-            description: "Checking for code formatting",
-            success: "Code formatting is correct",
-            failure: "Code formatting issues found",
-            file: "format.log",
-            command: "swiftformat --lint .",
+            id: "swift-format".into(),
+            description: "Checking for code formatting".into(),
+            success: "Code formatting is correct".into(),
+            failure: "Code formatting issues found".into(),
+            file: "format.log".into(),
+            command: "swiftformat --lint {files}".into(),
+            files: vec!["*.swift".into()],
+            ..Self::default()
         });
         hooks.push(Self {
             language: Language::Swift,
-            description: "Running unit tests",
-            success: "All tests passed",
-            failure: "Some tests failed",
-            file: "test.log",
-            command: "swift test",
+            id: "swift-test".into(),
+            description: "Running unit tests".into(),
+            success: "All tests passed".into(),
+            failure: "Some tests failed".into(),
+            file: "test.log".into(),
+            command: "swift test".into(),
+            depends_on: vec!["swift-build".into()],
+            ..Self::default()
         });
         hooks.push(Self {
             language: Language::Swift,
-            description: "Checking for security vulnerabilities",
-            success: "No vulnerabilities found",
-            failure: "Vulnerabilities found",
-            file: "audit.log",
-            command: "swift package audit",
+            id: "swift-audit".into(),
+            description: "Checking for security vulnerabilities".into(),
+            success: "No vulnerabilities found".into(),
+            failure: "Vulnerabilities found".into(),
+            file: "audit.log".into(),
+            command: "swift package audit".into(),
+            ..Self::default()
         });
         hooks.push(Self {
             language: Language::Swift,
-            description: "Building the project",
-            success: "Build successful",
-            failure: "Build failed",
-            file: "build.log",
-            command: "swift build",
+            id: "swift-build".into(),
+            description: "Building the project".into(),
+            success: "Build successful".into(),
+            failure: "Build failed".into(),
+            file: "build.log".into(),
+            command: "swift build".into(),
+            ..Self::default()
         });
         hooks.push(Self {
             language: Language::Swift,
-            description: "Running integration tests",
-            success: "All integration tests passed",
-            failure: "Some integration tests failed",
-            file: "integration.log",
-            command: "swift test --parallel",
+            id: "swift-integration".into(),
+            description: "Running integration tests".into(),
+            success: "All integration tests passed".into(),
+            failure: "Some integration tests failed".into(),
+            file: "integration.log".into(),
+            command: "swift test --parallel".into(),
+            depends_on: vec!["swift-build".into()],
+            ..Self::default()
         });
     }
     pub fn dart(hooks: &mut Vec<Self>) {
         hooks.push(Self {
             language: Language::Dart,
-            description: "Checking for code formatting",
-            success: "Code formatting is correct",
-            failure: "Code formatting issues found",
-            file: "format.log",
-            command: "dart format --set-exit-if-changed",
+            id: "dart-format".into(),
+            description: "Checking for code formatting".into(),
+            success: "Code formatting is correct".into(),
+            failure: "Code formatting issues found".into(),
+            file: "format.log".into(),
+            command: "dart format --set-exit-if-changed {files}".into(),
+            files: vec!["*.dart".into()],
+            ..Self::default()
         });
         hooks.push(Self {
             language: Language::Dart,
-            description: "Running unit tests",
-            success: "All tests passed",
-            failure: "Some tests failed",
-            file: "test.log",
-            command: "dart test",
+            id: "dart-test".into(),
+            description: "Running unit tests".into(),
+            success: "All tests passed".into(),
+            failure: "Some tests failed".into(),
+            file: "test.log".into(),
+            command: "dart test".into(),
+            ..Self::default()
         });
         hooks.push(Self {
             language: Language::Dart,
-            description: "Checking for security vulnerabilities",
-            success: "No vulnerabilities found",
-            failure: "Vulnerabilities found",
-            file: "audit.log",
-            command: "dart pub audit",
+            id: "dart-audit".into(),
+            description: "Checking for security vulnerabilities".into(),
+            success: "No vulnerabilities found".into(),
+            failure: "Vulnerabilities found".into(),
+            file: "audit.log".into(),
+            command: "dart pub audit".into(),
+            ..Self::default()
         });
         hooks.push(Self {
             language: Language::Dart,
-            description: "Building the project",
-            success: "Build successful",
-            failure: "Build failed",
-            file: "build.log",
-            command: "dart compile exe bin/main.dart",
+            id: "dart-build".into(),
+            description: "Building the project".into(),
+            success: "Build successful".into(),
+            failure: "Build failed".into(),
+            file: "build.log".into(),
+            command: "dart compile exe bin/main.dart".into(),
+            ..Self::default()
         });
     }
     pub fn kotlin(hooks: &mut Vec<Self>) {
         hooks.push(Self {
             language: Language::Kotlin,
-            description: "Running unit tests",
-            success: "All tests passed",
-            failure: "Some tests failed",
-            file: "test.log",
-            command: "gradle test",
+            id: "kotlin-test".into(),
+            description: "Running unit tests".into(),
+            success: "All tests passed".into(),
+            failure: "Some tests failed".into(),
+            file: "test.log".into(),
+            command: "gradle test".into(),
+            ..Self::default()
         });
     }
     pub fn elixir(hooks: &mut Vec<Self>) {
         hooks.push(Self {
             language: Language::Elixir,
-            description: "Checking for code formatting",
-            success: "Code formatting is correct",
-            failure: "Code formatting issues found",
-            file: "format.log",
-            command: "mix format --check-formatted",
+            id: "elixir-format".into(),
+            description: "Checking for code formatting".into(),
+            success: "Code formatting is correct".into(),
+            failure: "Code formatting issues found".into(),
+            file: "format.log".into(),
+            command: "mix format --check-formatted {files}".into(),
+            files: vec!["*.{ex,exs,heex}".into()],
+            ..Self::default()
         });
         hooks.push(Self {
             language: Language::Elixir,
-            description: "Running unit tests",
-            success: "All tests passed",
-            failure: "Some tests failed",
-            file: "test.log",
-            command: "mix test",
+            id: "elixir-test".into(),
+            description: "Running unit tests".into(),
+            success: "All tests passed".into(),
+            failure: "Some tests failed".into(),
+            file: "test.log".into(),
+            command: "mix test".into(),
+            depends_on: vec!["elixir-build".into()],
+            ..Self::default()
         });
         hooks.push(Self {
             language: Language::Elixir,
-            description: "Generating documentation",
-            success: "Documentation generated successfully",
-            failure: "Documentation generation failed",
-            file: "docs.log",
-            command: "mix docs",
+            id: "elixir-docs".into(),
+            description: "Generating documentation".into(),
+            success: "Documentation generated successfully".into(),
+            failure: "Documentation generation failed".into(),
+            file: "docs.log".into(),
+            command: "mix docs".into(),
+            depends_on: vec!["elixir-build".into()],
+            ..Self::default()
         });
         hooks.push(Self {
             language: Language::Elixir,
-            description: "Checking for security vulnerabilities",
-            success: "No vulnerabilities found",
-            failure: "Vulnerabilities found",
-            file: "audit.log",
-            command: "mix audit",
+            id: "elixir-audit".into(),
+            description: "Checking for security vulnerabilities".into(),
+            success: "No vulnerabilities found".into(),
+            failure: "Vulnerabilities found".into(),
+            file: "audit.log".into(),
+            command: "mix audit".into(),
+            ..Self::default()
         });
         hooks.push(Self {
             language: Language::Elixir,
-            description: "Building the project",
-            success: "Build successful",
-            failure: "Build failed",
-            file: "build.log",
-            command: "mix compile",
+            id: "elixir-build".into(),
+            description: "Building the project".into(),
+            success: "Build successful".into(),
+            failure: "Build failed".into(),
+            file: "build.log".into(),
+            command: "mix compile".into(),
+            ..Self::default()
         });
     }
+    ///
+    /// Returns the hooks to run for `language` according to the project configuration.
+    ///
+    /// The hooks declared in `breathes.toml` for this language are appended to the
+    /// built-in ones. With `builtin_hooks = "replace"`, a language declaring at least
+    /// one hook runs only its declared hooks. The `[run] timeout` applies to the hooks
+    /// without a timeout of their own.
+    ///
+    /// # Example
+    /// ```rust
+    /// use breathes::config::Config;
+    /// use breathes::hooks::{Hook, Language};
+    ///
+    /// let config: Config = r#"
+    /// builtin_hooks = "replace"
+    ///
+    /// [[hooks]]
+    /// language = "rust"
+    /// description = "Checking dependency licenses"
+    /// command = "cargo deny check"
+    /// "#.parse().unwrap();
+    ///
+    /// let hooks = Hook::configured(Language::Rust, &config);
+    /// assert_eq!(hooks.len(), 1);
+    /// assert_eq!(hooks[0].command, "cargo deny check");
+    /// assert!(!Hook::configured(Language::Go, &config).is_empty());
+    /// ```
+    #[must_use]
+    pub fn configured(language: Language, config: &Config) -> Vec<Self> {
+        Self::with_config(Self::get(language), language, config)
+    }
+
+    ///
+    /// Returns the hooks to run for the project of `language` in `dir` according to the
+    /// project configuration.
+    ///
+    /// Unlike [`Hook::configured`], the built-in hooks are adapted to the project: the
+    /// JavaScript and TypeScript hooks use its package manager and run only the scripts of
+    /// its `package.json`, and the Python hooks run only the tools it uses, in its
+    /// environment. Without a readable manifest, no script is run and the other hooks use
+    /// the package manager of the lockfile.
+    ///
+    #[must_use]
+    pub fn configured_in(language: Language, config: &Config, dir: &Path) -> Vec<Self> {
+        let mut builtin = Self::get(language);
+        if matches!(language, Language::Javascript | Language::Typescript) {
+            let package = Package::read(dir).unwrap_or_else(|_| Package {
+                manager: PackageManager::detect(dir, None),
+                scripts: Vec::new(),
+            });
+            package.adapt(&mut builtin);
+        }
+        if language == Language::Python {
+            Toolchain::read(dir).adapt(&mut builtin);
+        }
+        Self::with_config(builtin, language, config)
+    }
+
+    fn with_config(mut builtin: Vec<Self>, language: Language, config: &Config) -> Vec<Self> {
+        if config.run.sarif {
+            for hook in builtin
+                .iter_mut()
+                .filter(|hook| hook.output == OutputFormat::CargoJson)
+            {
+                hook.command = cargo_json(&hook.command).into();
+            }
+        }
+        let custom: Vec<Self> = config
+            .hooks
+            .iter()
+            .filter(|hook| hook.language == language)
+            .map(Self::from)
+            .collect();
+        let mut hooks = if config.builtin_hooks == BuiltinHooks::Replace && !custom.is_empty() {
+            Vec::new()
+        } else {
+            builtin
+        };
+        hooks.extend(custom);
+        hooks.retain_mut(|hook| {
+            let Some(over) = config.overrides.get(hook.id.as_ref()) else {
+                return true;
+            };
+            if let Some(command) = &over.command {
+                hook.command = command.clone().into();
+            }
+            if let Some(warn_only) = over.warn_only {
+                hook.warn_only = warn_only;
+            }
+            if let Some(output) = over.output {
+                hook.output = output;
+            }
+            if let Some(timeout) = over.timeout {
+                hook.timeout = Some(Duration::from_secs(timeout));
+            }
+            if let Some(depends_on) = &over.depends_on {
+                hook.depends_on = depends_on.iter().cloned().map(Cow::Owned).collect();
+            }
+            if let Some(parallel) = over.parallel {
+                hook.parallel = parallel;
+            }
+            if let Some(files) = &over.files {
+                hook.files = files.iter().cloned().map(Cow::Owned).collect();
+            }
+            over.enabled
+        });
+        if let Some(timeout) = config.run.timeout {
+            for hook in &mut hooks {
+                hook.timeout.get_or_insert(Duration::from_secs(timeout));
+            }
+        }
+        hooks
+    }
+
+    ///
+    /// Returns the shell commands running this hook on `files`, relative to the project root.
+    ///
+    /// A command using `{files}` is given the files matching the `files` patterns, split
+    /// across as many commands as needed to stay below [`MAX_COMMAND_LENGTH`]. When no file
+    /// matches, there is no command to run. Other commands are returned as is.
+    ///
+    /// # Errors
+    /// - If a pattern of `files` is not a valid glob.
+    ///
+    /// # Example
+    /// ```rust
+    /// use breathes::hooks::Hook;
+    /// use std::path::PathBuf;
+    ///
+    /// let hook = Hook {
+    ///     command: "swiftformat --lint {files}".into(),
+    ///     files: vec!["*.swift".into()],
+    ///     ..Hook::default()
+    /// };
+    /// let files = [PathBuf::from("README.md"), PathBuf::from("Sources/App/main.swift")];
+    /// assert_eq!(hook.commands(&files).unwrap(), ["swiftformat --lint Sources/App/main.swift"]);
+    /// assert!(hook.commands(&files[..1]).unwrap().is_empty());
+    /// ```
+    pub fn commands(&self, files: &[PathBuf]) -> Result<Vec<String>, Error> {
+        if !self.command.contains(FILES_PLACEHOLDER) {
+            return Ok(vec![self.command.to_string()]);
+        }
+        let patterns = glob_set(&self.files)?;
+        let files: Vec<&Path> = files
+            .iter()
+            .map(PathBuf::as_path)
+            .filter(|file| self.files.is_empty() || patterns.is_match(file))
+            .collect();
+        Ok(fill(&self.command, &files, MAX_COMMAND_LENGTH))
+    }
+
+    ///
+    /// Returns the identifiers of every built-in hook, for all languages.
+    ///
+    /// ```rust
+    /// use breathes::hooks::Hook;
+    /// assert!(Hook::builtin_ids().contains(&"rust-outdated".to_string()));
+    /// ```
+    #[must_use]
+    pub fn builtin_ids() -> Vec<String> {
+        Language::ALL
+            .into_iter()
+            .flat_map(Self::get)
+            .map(|hook| hook.id.into_owned())
+            .collect()
+    }
+
     #[must_use]
     pub fn get(language: Language) -> Vec<Self> {
         let mut hooks: Vec<Self> = vec![];
         match language {
-            Language::Unknown | Language::R => {}
+            Language::Unknown => {}
+            Language::R => Self::r(&mut hooks),
             Language::Kotlin => Self::kotlin(&mut hooks),
             Language::Typescript => Self::typescript(&mut hooks),
             Language::D => Self::d(&mut hooks),
@@ -1128,36 +1702,198 @@ impl Hook {
         hooks
     }
 }
+
+/// Makes the Cargo `command` print its messages as JSON, for the SARIF report.
+fn cargo_json(command: &str) -> String {
+    match command
+        .strip_prefix("cargo ")
+        .and_then(|rest| rest.split_once(' '))
+    {
+        Some((subcommand, args)) => format!("cargo {subcommand} --message-format=json {args}"),
+        None => format!("{command} --message-format=json"),
+    }
+}
+
+/// The outcome of [`verify`]: the report of the hooks of one language.
+pub type VerifyResult = Result<LanguageReport, Error>;
+
+impl From<&HookConfig> for Hook {
+    fn from(hook: &HookConfig) -> Self {
+        let file = hook.file.clone().unwrap_or_else(|| {
+            let slug: String = hook
+                .description
+                .chars()
+                .map(|c| {
+                    if c.is_ascii_alphanumeric() {
+                        c.to_ascii_lowercase()
+                    } else {
+                        '-'
+                    }
+                })
+                .collect();
+            format!("{}.log", slug.trim_matches('-'))
+        });
+        let id = hook.id.clone().unwrap_or_else(|| {
+            format!(
+                "{}-{}",
+                hook.language.to_string().to_ascii_lowercase(),
+                file.trim_end_matches(".log")
+            )
+        });
+        Self {
+            language: hook.language,
+            id: id.into(),
+            description: hook.description.clone().into(),
+            success: hook
+                .success
+                .clone()
+                .unwrap_or_else(|| String::from("Hook passed"))
+                .into(),
+            failure: hook
+                .failure
+                .clone()
+                .unwrap_or_else(|| String::from("Hook failed"))
+                .into(),
+            file: file.into(),
+            command: hook.command.clone().into(),
+            warn_only: hook.warn_only,
+            output: hook.output,
+            timeout: hook.timeout.map(Duration::from_secs),
+            depends_on: hook.depends_on.iter().cloned().map(Cow::Owned).collect(),
+            parallel: hook.parallel,
+            files: hook.files.iter().cloned().map(Cow::Owned).collect(),
+        }
+    }
+}
+
 ///
 /// Executes a set of parallel verification hooks for detected programming languages and provides
 /// a summarized view of the results, including their status (success, failure, or error) and execution time.
 ///
 /// # Description
 /// The `run_hooks` function performs the following steps:
-/// 1. Detects the available programming languages using the `detect` function.
-/// 2. If no languages are detected, it returns an error.
+/// 1. Finds the projects of the current directory and its subdirectories using [`discover`].
+/// 2. With `[run] snapshot`, sets the unstaged changes aside or exports the index, so that
+///    the hooks check what will be committed (see [`Snapshot`]). With `[run] staged`, only
+///    the projects touched by the staged changes are kept (see [`touched`]). If no project
+///    is left, nothing needs checking and an empty, successful report is returned.
 /// 3. Initializes a progress bar to visually inform the user about the progress of the hook executions.
-/// 4. Runs the verification hooks for each detected language in parallel using `into_par_iter()`.
-/// 5. Collects the results of the hook executions, including whether each one succeeded or failed,
-///    along with the time taken for execution.
-/// 6. Returns a success or failure based on the aggregate status of the hooks.
+/// 4. Runs the verification hooks of each project in parallel using `into_par_iter()`, in the
+///    directory of the project, and the independent hooks of a project concurrently, up to
+///    `[run] jobs` hooks at once.
+/// 5. Collects the outcome of each hook: its status, exit code, signal, wall time and log files.
+/// 6. Prints the failed hooks and the aggregate status, then returns the [`RunReport`].
 ///
 /// # Returns
-/// - `Ok(0)` on successful execution when all hooks are successfully verified.
-/// - `Err(Error)` if an error occurs or one or more hooks fail.
+/// - A [`RunReport`] holding the outcome of every hook, whether they passed or not.
 ///
 /// # Errors
-/// - Returns `Err(Error::other("No language detected"))` if no programming languages are found.
+/// - Returns an error if `breathes.toml` exists but is malformed.
+/// - Returns an error if `[run] staged` is set and the staged files cannot be listed.
+/// - Returns an error if the snapshot of `[run] snapshot` cannot be taken or restored.
+/// - Returns an error if the log directories or files cannot be created.
 /// # Panics
 /// - This function will panic if the provided progress bar style template cannot be set.
-pub fn run_hooks() -> Result<i32, Error> {
+pub fn run_hooks() -> Result<RunReport, Error> {
+    run_hooks_with(&Config::load()?)
+}
+
+///
+/// Same as [`run_hooks`], using an already loaded project configuration.
+///
+/// # Errors
+/// - Returns an error if `[run] staged` is set and the staged files cannot be listed.
+/// - Returns an error if the snapshot of `[run] snapshot` cannot be taken or restored.
+/// - Returns an error if the log directories or files cannot be created.
+/// # Panics
+/// - This function will panic if the provided progress bar style template cannot be set.
+pub fn run_hooks_with(config: &Config) -> Result<RunReport, Error> {
+    run_hooks_with_events(config, &NoEvents, true)
+}
+
+///
+/// Same as [`run_hooks_with`], sending every [`Event`] of the run to `events`.
+///
+/// When `progress` is `false`, the progress bars and the summary are not displayed, so
+/// that the standard output can carry the events alone.
+///
+/// # Example
+/// ```rust,no_run
+/// use breathes::config::Config;
+/// use breathes::events::JsonLines;
+/// use breathes::hooks::run_hooks_with_events;
+/// use std::io::stdout;
+///
+/// let report = run_hooks_with_events(&Config::default(), &JsonLines::new(stdout()), false);
+/// ```
+///
+/// # Errors
+/// - Returns an error if `[run] staged` is set and the staged files cannot be listed.
+/// - Returns an error if the snapshot of `[run] snapshot` cannot be taken or restored.
+/// - Returns an error if the log directories or files cannot be created.
+/// # Panics
+/// - This function will panic if the provided progress bar style template cannot be set.
+pub fn run_hooks_with_events(
+    config: &Config,
+    events: &dyn EventSink,
+    progress: bool,
+) -> Result<RunReport, Error> {
     let start = Instant::now();
-    let l = detect();
-    let multi = MultiProgress::new();
-    if l.is_empty() {
-        return Err(Error::other("No language detected"));
+    let snapshot = Snapshot::take(config.run.snapshot)?;
+    let checkout = snapshot.dir();
+    let mut projects = discover(checkout);
+    let multi = if progress {
+        MultiProgress::new()
+    } else {
+        MultiProgress::with_draw_target(ProgressDrawTarget::hidden())
+    };
+    let staged = if config.run.staged && !projects.is_empty() {
+        Some(staged_files()?)
+    } else {
+        None
+    };
+    let roots: Vec<PathBuf> = projects
+        .iter()
+        .map(|project| project.root.clone())
+        .collect();
+    if let Some(staged) = &staged {
+        projects = touched(&projects, staged);
     }
-    let pb = ProgressBar::new(l.len() as u64);
+    if projects.is_empty() {
+        snapshot.restore()?;
+        if progress {
+            if staged.is_some() {
+                println!("No staged file touches a project, nothing to check.");
+            } else {
+                println!("No language detected, nothing to check.");
+            }
+        }
+        return Ok(RunReport {
+            languages: Vec::new(),
+            duration: start.elapsed(),
+        });
+    }
+    let jobs = config
+        .run
+        .jobs
+        .map_or_else(Jobs::available, Jobs::new)
+        .with_fail_fast(config.run.fail_fast)
+        .with_missing_tools(config.run.missing_tools);
+    let mut languages: Vec<Language> = Vec::new();
+    for project in &projects {
+        if !languages.contains(&project.language) {
+            languages.push(project.language);
+        }
+    }
+    events.emit(&Event::RunStarted {
+        repository: config.repository.clone(),
+        languages,
+    });
+    let pb = if progress {
+        ProgressBar::new(projects.len() as u64)
+    } else {
+        ProgressBar::hidden()
+    };
     pb.set_style(
         ProgressStyle::with_template(
             "{spinner:.white} [{elapsed_precise}] [{bar:40.white}] {pos}/{len} {msg}",
@@ -1166,54 +1902,121 @@ pub fn run_hooks() -> Result<i32, Error> {
         .progress_chars("#>-"),
     );
     // Informe l'utilisateur que le scan commence
-    println!("Running hooks in parallel...");
+    match &config.repository {
+        Some(repository) if progress => {
+            println!("Running hooks for {repository} in parallel...");
+        }
+        None if progress => println!("Running hooks in parallel..."),
+        _ => {}
+    }
 
-    // Exécution parallèle : map chaque langage vers son résultat de vérification
-    let results: Vec<(Language, Result<(bool, u64), Error>)> = l
+    // Exécution parallèle : map chaque projet vers son résultat de vérification
+    let languages: Vec<LanguageReport> = projects
         .into_par_iter()
-        .map(|lang| {
-            let pb_lang = multi.add(ProgressBar::new(Hook::get(lang).len() as u64));
+        .map(|project| {
+            let Project {
+                language: lang,
+                ref root,
+            } = project;
+            let hooks = Hook::configured_in(lang, config, &checkout.join(root));
+            events.emit(&Event::LanguageDetected {
+                language: lang,
+                root: root.clone(),
+                hooks: hooks.len(),
+            });
+            let pb_lang = multi.add(ProgressBar::new(hooks.len() as u64));
             pb_lang.set_style(
                 ProgressStyle::with_template(
                     "{spinner:.white} {prefix:.bold} [{bar:20.white}] {pos}/{len} {msg}",
                 )
                 .unwrap(),
             );
-            pb_lang.set_prefix(lang.to_string());
-            pb_lang.set_message(lang.to_string());
-            let hooks = Hook::get(lang);
-            let res = verify(&hooks, &pb_lang);
+            pb_lang.set_prefix(project.to_string());
+            pb_lang.set_message(project.to_string());
+            let files = if hooks
+                .iter()
+                .any(|hook| hook.command.contains(FILES_PLACEHOLDER))
+            {
+                project_files(checkout, root, &roots, staged.as_deref())
+            } else {
+                Vec::new()
+            };
+            let res = verify(&hooks, checkout, &project, &files, &pb_lang, events, &jobs);
             pb_lang.finish_and_clear();
             pb.inc(1);
-            (lang, res)
+            res.inspect(|report| {
+                events.emit(&Event::LanguageFinished {
+                    language: lang,
+                    root: report.root.clone(),
+                    success: report.success(),
+                    duration_ms: report.duration.as_millis(),
+                });
+            })
         })
-        .collect();
+        .collect::<Result<_, Error>>()?;
     pb.finish_and_clear();
-    let mut global_success = true;
-
-    for (_lang, res) in &results {
-        match res {
-            Ok((status, _duration)) => {
-                if !status {
-                    global_success = false;
-                }
-            }
-            Err(_e) => {
-                global_success = false;
+    snapshot.restore()?;
+    let report = RunReport {
+        languages,
+        duration: start.elapsed(),
+    };
+
+    events.emit(&Event::RunFinished {
+        success: report.success(),
+        passed: report.count(HookStatus::Passed),
+        failed: report.count(HookStatus::Failed),
+        warned: report.count(HookStatus::Warned),
+        timed_out: report.count(HookStatus::TimedOut),
+        skipped: report.count(HookStatus::Skipped),
+        cancelled: report.count(HookStatus::Cancelled),
+        tool_missing: report.count(HookStatus::ToolMissing),
+        duration_ms: report.duration.as_millis(),
+    });
+    if !progress {
+        return Ok(report);
+    }
+    for language in &report.languages {
+        for hook in language
+            .hooks
+            .iter()
+            .filter(|hook| hook.status.is_failure())
+        {
+            if hook.status == HookStatus::ToolMissing {
+                println!(
+                    "  {} {} {}: {}",
+                    "!".red(),
+                    language.project(),
+                    hook.id,
+                    hook.message
+                );
+                continue;
             }
+            println!(
+                "  {} {} {}: {} (see {})",
+                "!".red(),
+                language.project(),
+                hook.id,
+                hook.message,
+                hook.stderr.display()
+            );
         }
     }
-
-    let final_status = if global_success { "SUCCESS" } else { "FAILURE" };
-    println!("\nOverall Status: {} (Total time: {}s)", final_status, start.elapsed().as_secs());
-
-    if !global_success {
-        return Err(Error::other("Checks failed. Check logs in ./breathes/"));
-    }
-    Ok(0)
+    let final_status = if report.success() {
+        "SUCCESS"
+    } else {
+        "FAILURE"
+    };
+    println!(
+        "\nOverall Status: {} (Total time: {}s)",
+        final_status,
+        report.duration.as_secs()
+    );
+    Ok(report)
 }
 /// Executes a given command, checks its exit status, and returns an appropriate result.
 ///
+/// The command runs in the directory set with [`Command::current_dir`], if any.
+///
 /// # Arguments
 ///
 /// * `cmd` - A mutable reference to a `Command` that represents the command to execute.
@@ -1223,7 +2026,7 @@ pub fn run_hooks() -> Result<i32, Error> {
 ///
 /// * `Ok(())` - If the command executes successfully and returns an exit status of 0.
 /// * `Err(Error)` - If the command fails to execute or returns a non-zero exit status. The error
-///                  contains the provided failure message.
+///   contains the provided failure message.
 ///
 /// # Errors
 ///
@@ -1236,9 +2039,9 @@ pub fn run_hooks() -> Result<i32, Error> {
 /// # Example
 ///
 /// ```rust
-/// use std::process::Command;
+/// use std::process::{Command, ExitStatus, Stdio};
 /// use breathes::hooks::ok;
-/// use std::io::Error;
+/// use std::io::{Error, ErrorKind};
 /// fn main() -> Result<(), Error> {
 ///     let mut cmd = Command::new("echo");
 ///     cmd.arg("Hello, world!");
@@ -1249,7 +2052,7 @@ pub fn run_hooks() -> Result<i32, Error> {
 /// ```
 /// ```
 pub fn ok(_desc: &str, cmd: &mut Command, _success: &str, failure: &str) -> Result<(), Error> {
-    let status = cmd.current_dir(".").spawn()?.wait()?.code();
+    let status = cmd.spawn()?.wait()?.code();
 
     if status == Some(0) {
         Ok(())
@@ -1263,140 +2066,442 @@ pub fn ok(_desc: &str, cmd: &mut Command, _success: &str, failure: &str) -> Resu
 ///
 /// This function takes a slice of `Hook` objects and performs the following operations:
 /// - Creates necessary directories to store logs
-/// - Executes each hook's command, capturing its standard output and error into separate files
+/// - Executes the hooks' commands concurrently, along their dependencies (see [`schedule`]),
+///   capturing their standard output and error into separate files
 /// - Tracks the success/failure of each hook's execution
 /// - Measures the total execution time
 ///
 /// # Arguments
 /// - `hooks`: A slice of [`Hook`] objects that represent the commands to be executed
+/// - `checkout`: The directory holding the files of the repository, `.` for the working tree
+///   (see [`Snapshot`])
+/// - `project`: The project the hooks belong to. Its `root`, relative to `checkout`, is the
+///   directory in which the commands run (`.` for the top-level project)
+/// - `files`: The files of the project, relative to `root`, given to the commands using
+///   `{files}` (see [`Hook::commands`])
+/// - `pb`: The progress bar advanced after each hook
+/// - `events`: The sink receiving a [`Event::HookStarted`] and a [`Event::HookFinished`] for each hook
+/// - `jobs`: The number of hooks allowed to run at the same time, and whether the run stops at
+///   the first failure
 ///
 /// # Returns
 /// Returns a `Result` containing:
-/// - A tuple `(bool, u64)`:
-///   - `bool`: Indicates whether all hooks were successfully executed (`true`) or if there were any failures (`false`)
-///   - `u64`: The total elapsed time in seconds for the verification process
-/// - An [`Error`] if any filesystem or execution operation fails
+/// - A [`LanguageReport`] with the outcome of each hook and the wall time of the verification
+/// - An [`Error`] if a log directory cannot be created
 ///
 /// # Behavior
-/// - If the `language` of the first hook is `Language::Unknown`, the function will immediately return `(true, 0)`
-///   without further processing.
+/// - If there is no hook, or the `language` of the project is `Language::Unknown`, the
+///   function immediately returns an empty report without further processing.
 /// - Creates directories named:
-///   - `breathes/<language>/stdout`
-///   - `breathes/<language>/stderr`
-///   where `<language>` is derived from the `language` field of the first hook.
-/// - Executes each hook's command using the appropriate shell:
+///   - `breathes/<root>/<language>/stdout`
+///   - `breathes/<root>/<language>/stderr`
+///
+///   at the top of the repository containing the current directory (in the current directory
+///   outside of a repository), where `<root>` is the directory of the project in the
+///   repository, left out for the top-level project.
+/// - Executes each hook's command in `root` using the appropriate shell, one command after
+///   the other when its `{files}` are split across several commands:
 ///   - On Windows: uses `cmd.exe` with the `/C` flag.
 ///   - On other platforms: uses `sh` with the `-c` flag.
 /// - Redirects the standard output and error of each executed command to files located in the respective directories.
-/// - Tracks whether each hook executes successfully:
-///   - Uses the `ok()` function to determine success or failure for a command execution.
-///   - Updates the `status` vector to maintain the results for all hooks.
+/// - Records the outcome of each hook in a [`HookReport`]:
+///   - A hook whose command exits with `0` has passed.
+///   - A failing hook marked `warn_only` is reported as warned and does not fail the verification.
+///   - A command which cannot be started is reported as failed, with the reason as its message.
+///   - A hook whose dependency did not pass is reported as skipped.
+///   - A hook using `{files}` is reported as skipped when none of `files` matches its patterns.
+///   - In fail-fast mode, the first failure kills the running hooks, reported as cancelled,
+///     and the hooks not started yet are reported as skipped.
 ///
 /// # Directories and Files
-/// - For each hook, the function generates log files:
-///   - `breathes/<language>/stdout/<hook.file>`: Stores standard output of the hook's command
-///   - `breathes/<language>/stderr/<hook.file>`: Stores standard error of the hook's command
+/// - For each hook, the function generates log files, at the top of the repository:
+///   - `breathes/<root>/<language>/stdout/<hook.file>`: Stores standard output of the hook's command
+///   - `breathes/<root>/<language>/stderr/<hook.file>`: Stores standard error of the hook's command
 ///
 /// # Errors
 /// - Fails if:
 ///   - Directory creation using `create_dir_all` fails
-///   - Unable to create log files for standard output or error
-///   - Hook command execution fails at any point
 ///
 /// # Example
 ///
-/// ```rust
-/// use std::process::Command;
+/// ```rust,no_run
+/// use std::process::{Command, ExitStatus, Stdio};
 /// use breathes::hooks::verify;
 /// use breathes::hooks::Hook;
 /// use breathes::hooks::Language;
-/// use std::io::Error;
+/// use std::io::{Error, ErrorKind};
+/// use breathes::events::NoEvents;
+/// use breathes::projects::Project;
+/// use breathes::scheduler::Jobs;
 /// use indicatif::ProgressBar;
+/// use std::path::Path;
 ///
 /// fn main() -> Result<(), Error> {
 ///     let hooks = vec![
 ///         Hook {
 ///         language: Language::Rust,
-///         description: "Running unit tests",
-///         success: "All tests passed",
-///         failure: "Some tests failed",
-///         file: "test.log",
-///         command: "cargo tree",
+///         description: "Running unit tests".into(),
+///         success: "All tests passed".into(),
+///         failure: "Some tests failed".into(),
+///         file: "test.log".into(),
+///         command: "cargo tree".into(),
+///         ..Hook::default()
 ///     }];
 ///     let pb = ProgressBar::new(1);
-///     let (success, duration) = verify(&hooks, &pb)?;
-///     assert!(success);
+///     let project = Project::new(Language::Rust, ".");
+///     let checkout = Path::new(".");
+///     let report = verify(&hooks, checkout, &project, &[], &pb, &NoEvents, &Jobs::new(2))?;
+///     assert!(report.success());
+///     assert_eq!(report.hooks[0].exit_code, Some(0));
 ///     Ok(())
 /// }
 /// ```
 ///
-/// # Notes
-/// - Error handling for the `ok()` function is not explicitly documented here; ensure that the function handles
-///   execution failures appropriately and updates `status` correctly.
-///
-pub fn verify(hooks: &[Hook], pb: &ProgressBar) -> Result<(bool, u64), Error> {
+pub fn verify(
+    hooks: &[Hook],
+    checkout: &Path,
+    project: &Project,
+    files: &[PathBuf],
+    pb: &ProgressBar,
+    events: &dyn EventSink,
+    jobs: &Jobs,
+) -> VerifyResult {
     let start = Instant::now();
-    let mut status: Vec<bool> = Vec::new();
-
-    create_dir_all("breathes")?;
+    let root = project.root.as_path();
+    let mut report = LanguageReport {
+        language: project.language,
+        root: root.to_path_buf(),
+        hooks: Vec::new(),
+        duration: Duration::ZERO,
+    };
 
-    if let Some(first_hook) = hooks.first() {
-        let lang = first_hook.language;
-        if lang == Language::Unknown {
-            return Ok((true, 0));
+    if !hooks.is_empty() {
+        if project.language == Language::Unknown {
+            return Ok(report);
         }
 
         // On prépare les chemins proprement une seule fois
-        let mut base_path = PathBuf::from("breathes");
-        base_path.push(lang.to_string());
+        let base_path = log_dir(project);
 
-        let stdout_dir = base_path.join("stdout");
-        let stderr_dir = base_path.join("stderr");
+        // Vérifie que les outils des hooks sont installés avant de lancer quoi que ce soit
+        let dir = checkout.join(root);
+        let missing = hooks
+            .iter()
+            .filter_map(|hook| {
+                let missing = missing_tool(&hook.command, &dir)?;
+                Some((hook.id.to_string(), missing))
+            })
+            .collect();
+        let context = Context {
+            dir,
+            missing,
+            root,
+            files,
+            stdout_dir: base_path.join("stdout"),
+            stderr_dir: base_path.join("stderr"),
+            pb,
+            events,
+            jobs,
+        };
 
         // Création des dossiers
-        create_dir_all(&stdout_dir)?;
-        create_dir_all(&stderr_dir)?;
+        create_dir_all(&context.stdout_dir)?;
+        create_dir_all(&context.stderr_dir)?;
 
-        for hook in hooks {
-            if hook.language == Language::Unknown {
-                continue;
-            }
-            pb.set_message(hook.description);
-            // On construit le chemin du fichier de log final
-            let out_file = stdout_dir.join(hook.file);
-            let err_file = stderr_dir.join(hook.file);
-
-            let mut cmd = if cfg!(target_os = "windows") {
-                let mut c = Command::new("cmd");
-                c.arg("/C").arg(hook.command);
-                c
-            } else {
-                let mut c = Command::new("sh");
-                c.arg("-c").arg(hook.command);
-                c
-            };
+        let hooks: Vec<Hook> = hooks
+            .iter()
+            .filter(|hook| hook.language != Language::Unknown)
+            .cloned()
+            .collect();
+        report.hooks = schedule(
+            &hooks,
+            jobs,
+            |hook| run_hook(hook, &context),
+            |hook, status, reason| {
+                let (title, label) = if status == HookStatus::Cancelled {
+                    ("Cancelled", "cancelled")
+                } else {
+                    ("Skipped", "skipped")
+                };
+                let skipped = HookReport {
+                    id: hook.id.to_string(),
+                    description: hook.description.to_string(),
+                    status,
+                    exit_code: None,
+                    signal: None,
+                    duration: Duration::ZERO,
+                    stdout: context.stdout_dir.join(hook.file.as_ref()),
+                    stderr: context.stderr_dir.join(hook.file.as_ref()),
+                    message: format!("{title}: {reason}"),
+                    output: hook.output,
+                };
+                pb.println(format!(
+                    "  {} {} ({label}: {reason})",
+                    "-".dark_grey(),
+                    hook.description
+                ));
+                pb.inc(1);
+                finished(events, hook, root, &skipped);
+                skipped
+            },
+        );
+    }
+    report.duration = start.elapsed();
+    Ok(report)
+}
+
+/// Returns the directory of the logs of `project`, `breathes/<root>/<language>` at the top of
+/// the repository containing the current directory, whatever the checkout the hooks run in.
+fn log_dir(project: &Project) -> PathBuf {
+    let git = |arg: &str| {
+        let output = Command::new("git")
+            .args(["rev-parse", arg])
+            .stderr(Stdio::null())
+            .output()
+            .ok()?;
+        let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        output.status.success().then_some(value)
+    };
+    let (top, prefix) = match (git("--show-toplevel"), git("--show-prefix")) {
+        (Some(top), Some(prefix)) => (PathBuf::from(top), PathBuf::from(prefix)),
+        _ => (PathBuf::from("."), PathBuf::new()),
+    };
+    let mut dir = top.join("breathes");
+    if !is_top_level(&prefix) {
+        dir.push(prefix);
+    }
+    if !project.is_top_level() {
+        dir.push(&project.root);
+    }
+    dir.push(project.language.to_string());
+    dir
+}
 
-            // Configuration commune de la commande
-            cmd.current_dir(".")
-                .stdout(File::create(out_file)?)
-                .stderr(File::create(err_file)?);
+/// Where the hooks of a project run and where their outcome goes.
+struct Context<'a> {
+    dir: PathBuf,
+    missing: HashMap<String, MissingTool>,
+    root: &'a Path,
+    files: &'a [PathBuf],
+    stdout_dir: PathBuf,
+    stderr_dir: PathBuf,
+    pb: &'a ProgressBar,
+    events: &'a dyn EventSink,
+    jobs: &'a Jobs,
+}
 
-            // On exécute
-            let result = ok(hook.description, &mut cmd, hook.success, hook.failure);
+/// Runs one hook, writing its output to its log files, and reports how it went.
+fn run_hook(hook: &Hook, context: &Context) -> HookReport {
+    let Context {
+        root, pb, events, ..
+    } = *context;
+    pb.set_message(hook.description.to_string());
+    events.emit(&Event::HookStarted {
+        language: hook.language,
+        root: root.to_path_buf(),
+        id: hook.id.to_string(),
+        description: hook.description.to_string(),
+    });
+    // On construit le chemin du fichier de log final
+    let out_file = context.stdout_dir.join(hook.file.as_ref());
+    let err_file = context.stderr_dir.join(hook.file.as_ref());
 
-            match result {
-                Ok(_) => pb.println(format!("  {} {}", "✓".green(), hook.description)),
-                Err(_) => pb.println(format!("  {} {}", "!".red(), hook.description)),
+    // On exécute
+    let hook_start = Instant::now();
+    let (status, exit_code, signal, message) =
+        if let Some(missing) = context.missing.get(hook.id.as_ref()) {
+            let status = context.jobs.missing_tools().status();
+            let message = match status {
+                HookStatus::Skipped => format!("Skipped: {missing}"),
+                _ => missing.to_string(),
+            };
+            (status, None, None, message)
+        } else {
+            match File::create(&out_file).and_then(|out| Ok((out, File::create(&err_file)?))) {
+                Ok((out, err)) => match hook.commands(context.files) {
+                    Ok(commands) if commands.is_empty() => (
+                        HookStatus::Skipped,
+                        None,
+                        None,
+                        String::from("Skipped: no matching files"),
+                    ),
+                    Ok(commands) => run_commands(hook, &commands, &out, &err, context),
+                    Err(e) => (
+                        failed_status(hook),
+                        None,
+                        None,
+                        format!("{}: {e}", hook.failure),
+                    ),
+                },
+                Err(e) => (
+                    failed_status(hook),
+                    None,
+                    None,
+                    format!("{}: {e}", hook.failure),
+                ),
             }
-            pb.inc(1);
-            if result.is_err() {
-                status.push(false);
-            } else {
-                status.push(true);
+        };
+
+    match status {
+        HookStatus::Passed => pb.println(format!("  {} {}", "✓".green(), hook.description)),
+        HookStatus::Warned => pb.println(format!(
+            "  {} {} (warn-only)",
+            "~".yellow(),
+            hook.description
+        )),
+        HookStatus::Failed => pb.println(format!("  {} {}", "!".red(), hook.description)),
+        HookStatus::TimedOut => {
+            pb.println(format!("  {} {} (timed out)", "!".red(), hook.description))
+        }
+        HookStatus::Cancelled => pb.println(format!(
+            "  {} {} (cancelled)",
+            "-".dark_grey(),
+            hook.description
+        )),
+        HookStatus::Skipped => pb.println(format!(
+            "  {} {} (skipped: {})",
+            "-".dark_grey(),
+            hook.description,
+            message.strip_prefix("Skipped: ").unwrap_or(&message)
+        )),
+        HookStatus::ToolMissing => {
+            pb.println(format!("  {} {} ({message})", "!".red(), hook.description))
+        }
+    }
+    pb.inc(1);
+    let report = HookReport {
+        id: hook.id.to_string(),
+        description: hook.description.to_string(),
+        status,
+        exit_code,
+        signal,
+        duration: hook_start.elapsed(),
+        stdout: out_file,
+        stderr: err_file,
+        message,
+        output: hook.output,
+    };
+    finished(events, hook, root, &report);
+    report
+}
+
+fn finished(events: &dyn EventSink, hook: &Hook, root: &Path, report: &HookReport) {
+    events.emit(&Event::HookFinished {
+        language: hook.language,
+        root: root.to_path_buf(),
+        id: report.id.clone(),
+        status: report.status,
+        exit_code: report.exit_code,
+        signal: report.signal,
+        duration_ms: report.duration.as_millis(),
+    });
+}
+
+/// Runs the commands of `hook` one after the other, until one of them does not pass.
+fn run_commands(
+    hook: &Hook,
+    commands: &[String],
+    out: &File,
+    err: &File,
+    context: &Context,
+) -> Outcome {
+    let mut outcome = (HookStatus::Passed, None, None, hook.success.to_string());
+    for command in commands {
+        let mut cmd = if cfg!(target_os = "windows") {
+            let mut c = Command::new("cmd");
+            c.arg("/C").arg(command);
+            c
+        } else {
+            let mut c = Command::new("sh");
+            c.arg("-c").arg(command);
+            c
+        };
+        outcome = match out.try_clone().and_then(|out| Ok((out, err.try_clone()?))) {
+            Ok((out, err)) => {
+                // Configuration commune de la commande
+                cmd.current_dir(&context.dir).stdout(out).stderr(err);
+                execute(hook, &mut cmd, context.jobs)
             }
+            Err(e) => (
+                failed_status(hook),
+                None,
+                None,
+                format!("{}: {e}", hook.failure),
+            ),
+        };
+        if outcome.0 != HookStatus::Passed {
+            break;
         }
     }
-    Ok((!status.contains(&false), start.elapsed().as_secs()))
+    outcome
+}
+
+/// The status, exit code, signal and message of a hook command.
+type Outcome = (HookStatus, Option<i32>, Option<i32>, String);
+
+/// Runs the command of `hook`, killing its process tree if it outlives the hook timeout, if
+/// the run is cancelled or if it is interrupted.
+fn execute(hook: &Hook, cmd: &mut Command, jobs: &Jobs) -> Outcome {
+    let result = spawn_group(cmd)
+        .and_then(|mut child| wait_or_kill(&mut child, hook.timeout, || jobs.is_cancelled()));
+    match result {
+        Ok(Waited::TimedOut(exit)) => (
+            HookStatus::TimedOut,
+            exit.code(),
+            exit_signal(exit),
+            format!(
+                "{}: timed out after {}s",
+                hook.failure,
+                hook.timeout.unwrap_or_default().as_secs()
+            ),
+        ),
+        Ok(Waited::Cancelled(exit)) => (
+            HookStatus::Cancelled,
+            exit.code(),
+            exit_signal(exit),
+            jobs.cancelled_by().map_or_else(
+                || String::from("Interrupted"),
+                |id| format!("Cancelled after `{id}` failed"),
+            ),
+        ),
+        Ok(Waited::Exited(exit)) if exit.success() => (
+            HookStatus::Passed,
+            exit.code(),
+            None,
+            hook.success.to_string(),
+        ),
+        Ok(Waited::Exited(exit)) => (
+            failed_status(hook),
+            exit.code(),
+            exit_signal(exit),
+            hook.failure.to_string(),
+        ),
+        Err(e) => (
+            failed_status(hook),
+            None,
+            None,
+            format!("{}: {e}", hook.failure),
+        ),
+    }
+}
+
+const fn failed_status(hook: &Hook) -> HookStatus {
+    if hook.warn_only {
+        HookStatus::Warned
+    } else {
+        HookStatus::Failed
+    }
+}
+
+#[cfg(unix)]
+fn exit_signal(status: ExitStatus) -> Option<i32> {
+    use std::os::unix::process::ExitStatusExt;
+    status.signal()
+}
+
+#[cfg(not(unix))]
+const fn exit_signal(_status: ExitStatus) -> Option<i32> {
+    None
 }
 
 /// Adds the specified `language` to the given vector `vec` if certain file conditions are met.
@@ -1463,13 +2568,12 @@ pub fn add_if_exists(file: &str, language: Language, vec: &mut Vec<Language>) {
 
 /// Detects and returns a list of programming languages based on predefined criteria.
 ///
-/// This function iterates through a collection of predefined programming languages and their
-/// associated file information (`LANGUAGES`). For each entry, it checks if the corresponding
-/// files exist, and if they do, adds the language to the resulting list.
+/// This function finds the projects of the current directory and its subdirectories with
+/// [`discover`], and returns each of their languages once.
 ///
 /// # Returns
 /// A `Vec<Language>` containing all programming languages that were detected based on
-/// the existence of their associated files.
+/// the existence of their associated files, at the top of the repository or below.
 ///
 /// # Attributes
 /// * `#[must_use]` - Indicates that the return value of this function should not be ignored
@@ -1487,17 +2591,40 @@ pub fn add_if_exists(file: &str, language: Language, vec: &mut Vec<Language>) {
 /// # Notes
 /// - This function depends on the global `LANGUAGES` collection, which maps programming
 ///   languages to their associated file data.
-/// - The `add_if_exists` function is responsible for determining if a language is added
-///   to the result based on file existence.
 ///
 /// # See Also
-/// - `add_if_exists` function for further details on how languages are added.
+/// - [`discover`] for the directory of each project.
 ///
 #[must_use]
 pub fn detect() -> Vec<Language> {
     let mut all: Vec<Language> = Vec::new();
-    for (l, file) in &LANGUAGES {
-        add_if_exists(file, *l, &mut all);
+    for project in discover(Path::new(".")) {
+        if !all.contains(&project.language) {
+            all.push(project.language);
+        }
     }
     all
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Hook, Language};
+    use crate::config::Config;
+    use crate::testing::Fixture;
+
+    #[test]
+    fn configured_in_drops_the_scripts_without_a_readable_manifest() {
+        let fixture = Fixture::new("configured-in");
+        fixture.write("yarn.lock", "");
+        let commands = || -> Vec<String> {
+            Hook::configured_in(Language::Javascript, &Config::default(), fixture.path())
+                .iter()
+                .map(|hook| hook.command.to_string())
+                .collect()
+        };
+        assert_eq!(commands(), ["yarn outdated", "yarn audit"]);
+
+        fixture.write("package.json", "{");
+        assert_eq!(commands(), ["yarn outdated", "yarn audit"]);
+    }
+}
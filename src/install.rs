@@ -0,0 +1,242 @@
+use std::fs::{create_dir_all, read_to_string, remove_file, rename, write};
+use std::io::{Error, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// The line identifying the hook scripts written by breathes.
+pub const MARKER: &str = "# Installed by breathes";
+
+/// The extension appended to a foreign hook script moved aside during installation.
+pub const BACKUP_EXTENSION: &str = "breathes-backup";
+
+///
+/// The git hooks managed by breathes, with the command each one runs.
+///
+/// * `pre-commit`: Runs the hooks of the projects touched by each commit.
+/// * `commit-msg`: Checks the message of each commit.
+/// * `pre-push`: Runs the hooks of the project before each push.
+///
+pub const GIT_HOOKS: [(&str, &str); 3] = [
+    ("pre-commit", "breathes run --staged"),
+    ("commit-msg", "breathes check-msg \"$1\""),
+    ("pre-push", "breathes run"),
+];
+
+///
+/// A hook script written or removed by [`install`] or [`uninstall`].
+///
+/// * `path`: The path of the hook script.
+/// * `backup`: The foreign script moved aside on install, or restored on uninstall.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HookFile {
+    pub path: PathBuf,
+    pub backup: Option<PathBuf>,
+}
+
+///
+/// Finds the git directory of the repository containing `start`.
+///
+/// The `.git` entry is searched in `start` and its parents. When it is a file, as in
+/// worktrees and submodules, the `gitdir:` line it contains is followed, and the
+/// `commondir` file of a worktree is honored so that the shared directory is returned.
+///
+/// # Errors
+/// - If `start` is not inside a git repository.
+/// - If a `.git` file cannot be read or does not contain a `gitdir:` line.
+///
+pub fn git_dir(start: &Path) -> Result<PathBuf, Error> {
+    let Some(dot_git) = start
+        .ancestors()
+        .map(|dir| dir.join(".git"))
+        .find(|path| path.exists())
+    else {
+        return Err(Error::new(ErrorKind::NotFound, "Not a git repository"));
+    };
+    if dot_git.is_dir() {
+        return Ok(dot_git);
+    }
+    let content = read_to_string(&dot_git)?;
+    let Some(target) = content
+        .lines()
+        .find_map(|line| line.strip_prefix("gitdir:"))
+    else {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("{}: missing gitdir line", dot_git.display()),
+        ));
+    };
+    let base = dot_git.parent().unwrap_or(start);
+    let dir = base.join(target.trim());
+    let dir = match read_to_string(dir.join("commondir")) {
+        Ok(common) => dir.join(common.trim()),
+        Err(_) => dir,
+    };
+    Ok(dir.canonicalize().unwrap_or(dir))
+}
+
+///
+/// Finds the directory git reads its hooks from.
+///
+/// The `core.hooksPath` setting is honored when set. A relative value is resolved from
+/// the top of the working tree, as git does. Otherwise the `hooks` directory of the
+/// shared git directory is used.
+///
+/// # Errors
+/// - If `start` is not inside a git repository.
+///
+pub fn hooks_dir(start: &Path) -> Result<PathBuf, Error> {
+    if let Some(path) = git(start, &["config", "--get", "core.hooksPath"]) {
+        let path = PathBuf::from(path);
+        if path.is_absolute() {
+            return Ok(path);
+        }
+        let top = git(start, &["rev-parse", "--show-toplevel"])
+            .map_or_else(|| start.to_path_buf(), PathBuf::from);
+        return Ok(top.join(path));
+    }
+    Ok(git_dir(start)?.join("hooks"))
+}
+
+///
+/// Writes the breathes hook scripts in the hooks directory of the repository.
+///
+/// A script previously written by breathes is overwritten. Any other existing script is
+/// moved aside with the `.breathes-backup` extension, unless a backup already exists. The
+/// breathes script runs the backup first, with the same arguments, and stops if it fails,
+/// so that the previous hook keeps working.
+///
+/// # Errors
+/// - If `start` is not inside a git repository.
+/// - If a foreign hook exists and its backup slot is already taken.
+/// - If a script cannot be written.
+///
+/// # Example
+/// The hooks directory is read from the git configuration, which may point outside `root`:
+/// ```no_run
+/// use breathes::install::install;
+/// use std::env::temp_dir;
+/// use std::fs::{create_dir_all, read_to_string, remove_dir_all, write};
+///
+/// let root = temp_dir().join("breathes-install-doc");
+/// let _ = remove_dir_all(&root);
+/// create_dir_all(root.join(".git/hooks")).unwrap();
+/// write(root.join(".git/hooks/pre-commit"), "#!/bin/sh\nmake lint\n").unwrap();
+///
+/// let installed = install(&root).unwrap();
+/// let backup = installed[0].backup.as_ref().unwrap();
+/// assert!(backup.ends_with("pre-commit.breathes-backup"));
+/// let script = read_to_string(&installed[0].path).unwrap();
+/// assert!(script.contains("/pre-commit.breathes-backup\""));
+/// assert!(script.contains("\"$backup\" \"$@\" || exit $?"));
+/// remove_dir_all(&root).unwrap();
+/// ```
+pub fn install(start: &Path) -> Result<Vec<HookFile>, Error> {
+    let dir = hooks_dir(start)?;
+    create_dir_all(&dir)?;
+    let mut installed = Vec::new();
+    for (name, command) in GIT_HOOKS {
+        let path = dir.join(name);
+        let mut backup = None;
+        if path.exists() && !is_managed(&path) {
+            let target = path.with_extension(BACKUP_EXTENSION);
+            if target.exists() {
+                return Err(Error::new(
+                    ErrorKind::AlreadyExists,
+                    format!(
+                        "{} exists and {} is already taken",
+                        path.display(),
+                        target.display()
+                    ),
+                ));
+            }
+            rename(&path, &target)?;
+            backup = Some(target);
+        }
+        write(&path, script(name, command))?;
+        make_executable(&path)?;
+        installed.push(HookFile { path, backup });
+    }
+    Ok(installed)
+}
+
+///
+/// Removes the breathes hook scripts and restores the scripts moved aside by [`install`].
+///
+/// Scripts not written by breathes are left untouched.
+///
+/// # Errors
+/// - If `start` is not inside a git repository.
+/// - If a script cannot be removed or restored.
+///
+pub fn uninstall(start: &Path) -> Result<Vec<HookFile>, Error> {
+    let dir = hooks_dir(start)?;
+    let mut removed = Vec::new();
+    for (name, _) in GIT_HOOKS {
+        let path = dir.join(name);
+        if !is_managed(&path) {
+            continue;
+        }
+        remove_file(&path)?;
+        let target = path.with_extension(BACKUP_EXTENSION);
+        let mut backup = None;
+        if target.exists() {
+            rename(&target, &path)?;
+            backup = Some(target);
+        }
+        removed.push(HookFile { path, backup });
+    }
+    Ok(removed)
+}
+
+/// The hook script `name` running `command`, after the foreign hook it replaced, if any.
+fn script(name: &str, command: &str) -> String {
+    format!(
+        "#!/bin/sh\n{MARKER}\nbackup=\"${{0%/*}}/{name}.{BACKUP_EXTENSION}\"\nif [ -x \"$backup\" ]; then\n    \"$backup\" \"$@\" || exit $?\nfi\nexec {command}\n"
+    )
+}
+
+fn is_managed(path: &Path) -> bool {
+    read_to_string(path).is_ok_and(|content| content.lines().any(|line| line == MARKER))
+}
+
+fn git(dir: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .current_dir(dir)
+        .args(args)
+        .output()
+        .ok()?;
+    let value = String::from_utf8(output.stdout).ok()?;
+    let value = value.trim();
+    (output.status.success() && !value.is_empty()).then(|| value.to_string())
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> Result<(), Error> {
+    use std::fs::{Permissions, set_permissions};
+    use std::os::unix::fs::PermissionsExt;
+    set_permissions(path, Permissions::from_mode(0o755))
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> Result<(), Error> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::git_dir;
+    use crate::testing::Fixture;
+
+    #[test]
+    fn git_dir_follows_the_git_file_of_a_submodule() {
+        let fixture = Fixture::new("git-dir");
+        fixture
+            .write(".git/modules/sub/HEAD", "")
+            .write("sub/.git", "gitdir: ../.git/modules/sub\n");
+
+        let dir = git_dir(&fixture.path().join("sub")).unwrap();
+        assert!(dir.ends_with(".git/modules/sub"));
+        assert!(git_dir(fixture.path()).unwrap().ends_with(".git"));
+    }
+}
@@ -0,0 +1,193 @@
+use crate::report::{HookReport, HookStatus, LanguageReport, RunReport};
+use regex::Regex;
+use std::fs::read_to_string;
+use std::io::{Error, Write};
+use std::path::Path;
+
+/// The number of lines kept from the end of the standard error of a hook.
+pub const STDERR_TAIL_LINES: usize = 50;
+
+///
+/// Writes `report` as a JUnit XML document, as read by Jenkins, GitLab and most CI servers.
+///
+/// Each project is a `testsuite`, named after its language and its directory, and each hook
+/// a `testcase` named after its id. A failed
+/// hook carries a `failure` element with the failure text of the hook and the last
+/// [`STDERR_TAIL_LINES`] lines of its standard error. A warn-only hook which failed is
+/// reported as passed, with its standard error in `system-err`. A hook skipped because of a
+/// failed dependency, or skipped or cancelled in fail-fast mode, carries a `skipped` element
+/// and is counted in the `skipped` attribute of its suite.
+/// A hook whose tool is not installed fails with the `tool missing` type.
+///
+/// # Errors
+/// - If `out` cannot be written.
+///
+/// # Example
+/// ```rust
+/// use breathes::hooks::{Language, OutputFormat};
+/// use breathes::junit::write_junit;
+/// use breathes::report::{HookReport, HookStatus, LanguageReport, RunReport};
+/// use std::time::Duration;
+///
+/// let test = HookReport {
+///     id: "rust-test".into(),
+///     description: "Testing your project".into(),
+///     status: HookStatus::Failed,
+///     exit_code: Some(101),
+///     signal: None,
+///     duration: Duration::from_millis(1500),
+///     stdout: "breathes/Rust/stdout/test.log".into(),
+///     stderr: "breathes/Rust/stderr/test.log".into(),
+///     message: "Tests failed <2 errors>".into(),
+///     output: OutputFormat::Text,
+/// };
+/// let doc = HookReport {
+///     id: "rust-doc".into(),
+///     status: HookStatus::Skipped,
+///     exit_code: None,
+///     duration: Duration::ZERO,
+///     message: "Skipped: `rust-check` did not pass".into(),
+///     ..test.clone()
+/// };
+/// let report = RunReport {
+///     languages: vec![LanguageReport {
+///         language: Language::Rust,
+///         root: ".".into(),
+///         hooks: vec![test, doc],
+///         duration: Duration::from_millis(1500),
+///     }],
+///     duration: Duration::from_millis(1600),
+/// };
+/// let mut xml = Vec::new();
+/// write_junit(&report, &mut xml).unwrap();
+/// let xml = String::from_utf8(xml).unwrap();
+/// assert!(xml.contains(r#"<testsuites name="breathes" tests="2" failures="1" skipped="1""#));
+/// assert!(xml.contains(r#"<testsuite name="Rust" tests="2" failures="1" skipped="1""#));
+/// assert!(xml.contains(r#"<failure message="Tests failed &lt;2 errors&gt;" type="exit code 101">"#));
+/// ```
+pub fn write_junit(report: &RunReport, out: &mut impl Write) -> Result<(), Error> {
+    writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        out,
+        r#"<testsuites name="breathes" tests="{}" failures="{}" skipped="{}" time="{:.3}">"#,
+        report.hooks().count(),
+        report.failures().count(),
+        report
+            .hooks()
+            .filter(|(_, hook)| is_skipped(hook.status))
+            .count(),
+        report.duration.as_secs_f64()
+    )?;
+    for language in &report.languages {
+        write_suite(language, out)?;
+    }
+    writeln!(out, "</testsuites>")
+}
+
+fn write_suite(language: &LanguageReport, out: &mut impl Write) -> Result<(), Error> {
+    let name = escape(&language.project().to_string());
+    writeln!(
+        out,
+        r#"  <testsuite name="{name}" tests="{}" failures="{}" skipped="{}" time="{:.3}">"#,
+        language.hooks.len(),
+        language
+            .hooks
+            .iter()
+            .filter(|hook| hook.status.is_failure())
+            .count(),
+        language
+            .hooks
+            .iter()
+            .filter(|hook| is_skipped(hook.status))
+            .count(),
+        language.duration.as_secs_f64()
+    )?;
+    for hook in &language.hooks {
+        write!(
+            out,
+            r#"    <testcase name="{}" classname="{name}" time="{:.3}""#,
+            escape(&hook.id),
+            hook.duration.as_secs_f64()
+        )?;
+        match hook.status {
+            HookStatus::Passed => writeln!(out, "/>")?,
+            HookStatus::Failed | HookStatus::TimedOut | HookStatus::ToolMissing => {
+                writeln!(out, ">")?;
+                writeln!(
+                    out,
+                    r#"      <failure message="{}" type="{}">{}</failure>"#,
+                    escape(&hook.message),
+                    escape(&failure_type(hook)),
+                    escape(&stderr_tail(&hook.stderr))
+                )?;
+                writeln!(out, "    </testcase>")?;
+            }
+            HookStatus::Skipped | HookStatus::Cancelled => {
+                writeln!(out, ">")?;
+                writeln!(
+                    out,
+                    r#"      <skipped message="{}"/>"#,
+                    escape(&hook.message)
+                )?;
+                writeln!(out, "    </testcase>")?;
+            }
+            HookStatus::Warned => {
+                writeln!(out, ">")?;
+                writeln!(
+                    out,
+                    "      <system-err>{}</system-err>",
+                    escape(&stderr_tail(&hook.stderr))
+                )?;
+                writeln!(out, "    </testcase>")?;
+            }
+        }
+    }
+    writeln!(out, "  </testsuite>")
+}
+
+/// Whether `status` is written as a `skipped` test case.
+const fn is_skipped(status: HookStatus) -> bool {
+    matches!(status, HookStatus::Skipped | HookStatus::Cancelled)
+}
+
+fn failure_type(hook: &HookReport) -> String {
+    if hook.status == HookStatus::TimedOut {
+        return String::from("timeout");
+    }
+    if hook.status == HookStatus::ToolMissing {
+        return String::from("tool missing");
+    }
+    match (hook.exit_code, hook.signal) {
+        (Some(code), _) => format!("exit code {code}"),
+        (None, Some(signal)) => format!("signal {signal}"),
+        (None, None) => String::from("not started"),
+    }
+}
+
+/// Returns the last lines of a log file without colors, or nothing if it cannot be read.
+fn stderr_tail(path: &Path) -> String {
+    let content = read_to_string(path).unwrap_or_default();
+    let content = Regex::new(r"\x1b\[[0-9;?]*[ -/]*[@-~]").map_or(content.clone(), |ansi| {
+        ansi.replace_all(&content, "").into_owned()
+    });
+    let lines: Vec<&str> = content.lines().collect();
+    lines[lines.len().saturating_sub(STDERR_TAIL_LINES)..].join("\n")
+}
+
+/// Escapes the XML special characters, and drops the control characters XML 1.0 forbids.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if c.is_control() => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
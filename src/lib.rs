@@ -1,4 +1,41 @@
+/// Guided commit composer
+pub mod commit;
+/// Project policy loaded from breathes.toml
+pub mod config;
+/// Conventional Commits parser
+pub mod conventional;
+/// Machine-readable events of a hook run
+pub mod events;
+/// Fill the file lists of hook commands
+pub mod files;
 /// manage hooks
 pub mod hooks;
+/// Install breathes as git hooks
+pub mod install;
+/// JUnit XML output for hook runs
+pub mod junit;
+/// Check complete commit messages
+pub mod message;
+/// Package managers and scripts of JavaScript projects
+pub mod node;
+/// Run hook commands with deadlines
+pub mod process;
+/// Find the projects of a repository
+pub mod projects;
+/// Environments and tools of Python projects
+pub mod python;
+/// Structured outcome of a hook run
+pub mod report;
+/// SARIF output of the findings of hooks
+pub mod sarif;
+/// Run hooks concurrently along their dependencies
+pub mod scheduler;
+/// Check the staged state instead of the working tree
+pub mod snapshot;
+/// Temporary fixtures of the unit tests
+#[cfg(test)]
+mod testing;
+/// Find the tools run by hooks
+pub mod tools;
 /// Inquire validators
 pub mod validator;
@@ -0,0 +1,261 @@
+use breathes::commit::commit;
+use breathes::config::Config;
+use breathes::events::JsonLines;
+use breathes::hooks::{Hook, Language, run_hooks_with, run_hooks_with_events};
+use breathes::install::{HookFile, install, uninstall};
+use breathes::junit::write_junit;
+use breathes::message::check_message_file;
+use breathes::process::interrupted;
+use breathes::projects::{detect_projects, discover};
+use breathes::sarif::write_sarif;
+use breathes::snapshot::SnapshotMode;
+use breathes::tools::MissingTools;
+use clap::{Parser, Subcommand, ValueEnum};
+use crossterm::style::Stylize;
+use std::env::current_dir;
+use std::fs::File;
+use std::io::{BufWriter, Error, Write, stdout};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::str::FromStr;
+
+/// The exit code used when the checks ran and found problems.
+const CHECKS_FAILED: u8 = 1;
+/// The exit code used when breathes itself could not do its job.
+const BREATHES_ERROR: u8 = 2;
+/// The exit code used when the run was interrupted with Ctrl-C, as a shell reports it.
+const INTERRUPTED: u8 = 130;
+
+/// Check your source code and commit messages
+#[derive(Parser)]
+#[command(name = "breathes", version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Commands,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Run the hooks of every detected project
+    Run {
+        /// Also write the results as a JUnit XML report to this file
+        #[arg(long, value_name = "PATH")]
+        junit: Option<PathBuf>,
+        /// Also write the findings of the hooks as a SARIF report to this file
+        #[arg(long, value_name = "PATH")]
+        sarif: Option<PathBuf>,
+        /// Kill the hooks running longer than this number of seconds, unless they set their own timeout
+        #[arg(long, value_name = "SECONDS", value_parser = clap::value_parser!(u64).range(1..))]
+        timeout: Option<u64>,
+        /// The number of hooks allowed to run at the same time
+        #[arg(short, long, value_parser = clap::value_parser!(u64).range(1..))]
+        jobs: Option<u64>,
+        /// Stop at the first failing hook, killing the running ones
+        #[arg(long)]
+        fail_fast: bool,
+        /// Only run the hooks of the projects touched by the staged changes
+        #[arg(long, conflicts_with = "all")]
+        staged: bool,
+        /// Run the hooks of every project, even if `[run] staged` is set
+        #[arg(long)]
+        all: bool,
+        /// Check the staged state: none, stash (set the unstaged changes aside) or index (check a copy of the index)
+        #[arg(long, value_name = "MODE", value_parser = SnapshotMode::from_str)]
+        snapshot: Option<SnapshotMode>,
+        /// What to do with the hooks whose tool is not installed: fail (the default) or skip
+        #[arg(long, value_name = "POLICY", value_parser = MissingTools::from_str)]
+        missing_tools: Option<MissingTools>,
+        /// How to display the progress of the hooks
+        #[arg(long, value_enum, default_value_t = Format::Text)]
+        format: Format,
+    },
+    /// Print the languages detected in the current directory and its subdirectories, with
+    /// the confidence of each detection and the files which triggered it
+    Detect,
+    /// Print the hooks that would run
+    ListHooks {
+        /// List the built-in hooks of every language, not only the detected ones, without
+        /// reading breathes.toml
+        #[arg(long)]
+        all: bool,
+    },
+    /// Write a commit message interactively and commit the staged changes
+    Commit,
+    /// Check a commit message file, as given by git to the commit-msg hook
+    CheckMsg {
+        /// The file containing the commit message
+        file: PathBuf,
+    },
+    /// Install the pre-commit, commit-msg and pre-push git hooks
+    Install,
+    /// Remove the git hooks installed by breathes
+    Uninstall,
+}
+
+/// The display of `breathes run`.
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    /// Progress bars and a summary
+    Text,
+    /// One JSON object per line for each event of the run
+    Json,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let result = dispatch(cli.command);
+    if interrupted() {
+        return ExitCode::from(INTERRUPTED);
+    }
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::from(CHECKS_FAILED),
+        Err(e) => {
+            eprintln!("{} {e}", "error:".red().bold());
+            ExitCode::from(BREATHES_ERROR)
+        }
+    }
+}
+
+fn dispatch(command: Commands) -> Result<bool, Error> {
+    match command {
+        Commands::Run {
+            junit,
+            sarif,
+            timeout,
+            jobs,
+            fail_fast,
+            staged,
+            all,
+            snapshot,
+            missing_tools,
+            format,
+        } => {
+            let mut config = Config::load()?;
+            if timeout.is_some() {
+                config.run.timeout = timeout;
+            }
+            if let Some(jobs) = jobs {
+                config.run.jobs = usize::try_from(jobs).ok();
+            }
+            if fail_fast {
+                config.run.fail_fast = true;
+            }
+            if staged || all {
+                config.run.staged = staged;
+            }
+            if let Some(snapshot) = snapshot {
+                config.run.snapshot = snapshot;
+            }
+            if let Some(missing_tools) = missing_tools {
+                config.run.missing_tools = missing_tools;
+            }
+            config.run.sarif = sarif.is_some();
+            run(&config, format, junit.as_deref(), sarif.as_deref())
+        }
+        Commands::Detect => {
+            for detection in detect_projects(Path::new(".")) {
+                println!("{detection}");
+            }
+            Ok(true)
+        }
+        Commands::ListHooks { all: true } => {
+            list_hooks(&Config::default(), true);
+            Ok(true)
+        }
+        Commands::ListHooks { all: false } => {
+            list_hooks(&Config::load()?, false);
+            Ok(true)
+        }
+        Commands::Commit => commit(&Config::load()?),
+        Commands::CheckMsg { file } => check_msg(&Config::load()?, &file),
+        Commands::Install => {
+            print_hook_files(
+                "Installed",
+                "previous hook kept in",
+                &install(&current_dir()?)?,
+            );
+            Ok(true)
+        }
+        Commands::Uninstall => {
+            print_hook_files(
+                "Removed",
+                "previous hook restored from",
+                &uninstall(&current_dir()?)?,
+            );
+            Ok(true)
+        }
+    }
+}
+
+fn run(
+    config: &Config,
+    format: Format,
+    junit: Option<&Path>,
+    sarif: Option<&Path>,
+) -> Result<bool, Error> {
+    let report = match format {
+        Format::Text => run_hooks_with(config)?,
+        Format::Json => run_hooks_with_events(config, &JsonLines::new(stdout()), false)?,
+    };
+    if let Some(path) = junit {
+        write_file(path, |out| write_junit(&report, out))?;
+    }
+    if let Some(path) = sarif {
+        write_file(path, |out| write_sarif(&report, out))?;
+    }
+    Ok(report.success())
+}
+
+fn write_file(
+    path: &Path,
+    write: impl FnOnce(&mut BufWriter<File>) -> Result<(), Error>,
+) -> Result<(), Error> {
+    let mut out = BufWriter::new(File::create(path)?);
+    write(&mut out)?;
+    out.flush()
+}
+
+fn list_hooks(config: &Config, all: bool) {
+    let projects: Vec<(String, Vec<Hook>)> = if all {
+        Language::ALL
+            .into_iter()
+            .map(|language| (language.to_string(), Hook::configured(language, config)))
+            .collect()
+    } else {
+        discover(Path::new("."))
+            .into_iter()
+            .map(|project| {
+                let hooks = Hook::configured_in(project.language, config, &project.root);
+                (project.to_string(), hooks)
+            })
+            .collect()
+    };
+    for (name, hooks) in projects {
+        if hooks.is_empty() {
+            continue;
+        }
+        println!("{}", name.bold());
+        for hook in hooks {
+            let warn = if hook.warn_only { " (warn-only)" } else { "" };
+            println!("  {:<24} {}{warn}", hook.id, hook.command);
+        }
+    }
+}
+
+fn check_msg(config: &Config, file: &Path) -> Result<bool, Error> {
+    let diagnostics = check_message_file(file, config)?;
+    for diagnostic in &diagnostics {
+        eprintln!("{}:{diagnostic}", file.display());
+    }
+    Ok(diagnostics.is_empty())
+}
+
+fn print_hook_files(action: &str, backup_action: &str, files: &[HookFile]) {
+    for file in files {
+        println!("  {} {action} {}", "✓".green(), file.path.display());
+        if let Some(backup) = &file.backup {
+            println!("    {backup_action} {}", backup.display());
+        }
+    }
+}
@@ -0,0 +1,290 @@
+use crate::config::Config;
+use crate::conventional::{CommitMessage, ParseErrorKind};
+use crate::validator::{
+    BODY_MAX_LINE_LENGTH, DICTIONARY, SUMMARY_MAX_LENGTH, commit_type_validator,
+    validate_body_line_length, validate_not_empty, validate_scope, validate_spelling,
+    validate_summary_length, validate_summary_punctuation,
+};
+use inquire::CustomUserError;
+use inquire::validator::{ErrorMessage, Validation};
+use std::fmt::{Display, Formatter};
+use std::fs::read_to_string;
+use std::io::Error;
+use std::path::Path;
+
+/// The line below which git ignores the content of a commit message (`git commit -v`).
+pub const SCISSORS: &str = "# ------------------------ >8 ------------------------";
+
+/// The starts of the headers git writes itself, for merges, reverts and autosquash commits.
+pub const GENERATED_HEADERS: [&str; 10] = [
+    "Merge branch ",
+    "Merge remote-tracking branch ",
+    "Merge tag ",
+    "Merge commit ",
+    "Merge pull request ",
+    "Revert \"",
+    "fixup! ",
+    "squash! ",
+    "amend! ",
+    "Auto-merged ",
+];
+
+///
+/// Whether `header` was written by git rather than by the author, such as
+/// `Merge branch 'x'` or `fixup! feat: add a parser`. Such messages are not checked.
+///
+/// # Example
+/// ```rust
+/// use breathes::message::is_generated;
+///
+/// assert!(is_generated("Merge branch 'feature' into main"));
+/// assert!(is_generated("Revert \"feat: add a parser\""));
+/// assert!(is_generated("squash! feat: add a parser"));
+/// assert!(!is_generated("Merged the parsers"));
+/// ```
+#[must_use]
+pub fn is_generated(header: &str) -> bool {
+    GENERATED_HEADERS
+        .iter()
+        .any(|start| header.starts_with(start))
+}
+
+///
+/// A problem found in a commit message.
+///
+/// * `line`: The 1-based line of the problem in the message file.
+/// * `column`: The 1-based column of the problem, counted in characters.
+/// * `rule`: The name of the rule that failed (e.g. `summary-length`).
+/// * `message`: A description of the problem.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub line: usize,
+    pub column: usize,
+    pub rule: &'static str,
+    pub message: String,
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}:{}: {} [{}]",
+            self.line, self.column, self.message, self.rule
+        )
+    }
+}
+
+///
+/// Checks a complete commit message against every validator.
+///
+/// The message is read the way git writes it for the `commit-msg` hook: lines starting
+/// with `#` are comments, and everything below the scissors line is ignored. The first
+/// line is the header (`type(scope)!: summary`), followed by a blank line, the body and
+/// the trailing footers (`Token: value`, `BREAKING CHANGE: ...`), as parsed by
+/// [`CommitMessage`]. The footers are exempt from the body rules. The messages git writes
+/// itself for merges, reverts and `git commit --fixup` are not checked, see [`is_generated`].
+///
+/// # Rules
+/// * `header-format`: The header follows `type(scope)!: summary`, see [`ParseErrorKind`].
+/// * `type`: The type is one of the configured types.
+/// * `scope`: The scope follows the configured scopes and scope policy.
+/// * `summary-empty`, `summary-length`, `summary-punctuation`: The summary rules.
+/// * `body-leading-blank`: A blank line separates the header from the body.
+/// * `body-line-length`: The body lines are not too long.
+/// * `spelling`: The summary and the body are spelled correctly, when the dictionary is available.
+///
+/// # Example
+/// ```rust
+/// use breathes::config::Config;
+/// use breathes::message::check_message;
+///
+/// let diagnostics = check_message("feat: add a parser.\n", &Config::default());
+/// assert_eq!(diagnostics.len(), 1);
+/// assert_eq!(diagnostics[0].rule, "summary-punctuation");
+/// assert_eq!((diagnostics[0].line, diagnostics[0].column), (1, 19));
+///
+/// let diagnostics = check_message("oops\n", &Config::default());
+/// assert_eq!(diagnostics[0].rule, "header-format");
+///
+/// let config = Config::default();
+/// assert!(check_message("Merge branch 'x'\n", &config).is_empty());
+/// assert!(check_message("Revert \"feat: add a parser\"\n\nThis reverts commit 1a2b3c.\n", &config).is_empty());
+/// assert!(check_message("fixup! feat: add a parser\n", &config).is_empty());
+/// assert!(check_message("squash! feat: add a parser\n\nkeep the tests\n", &config).is_empty());
+/// ```
+#[must_use]
+pub fn check_message(content: &str, config: &Config) -> Vec<Diagnostic> {
+    let lines = meaningful_lines(content);
+    if lines
+        .first()
+        .is_some_and(|(_, header)| is_generated(header))
+    {
+        return Vec::new();
+    }
+    let text = lines
+        .iter()
+        .map(|(_, line)| *line)
+        .collect::<Vec<_>>()
+        .join("\n");
+    let number = |index: usize| lines.get(index).map_or(1, |(line, _)| *line);
+    let spelling = Path::new(DICTIONARY).is_file();
+    let mut diagnostics = Vec::new();
+
+    let (header, body) = match text.parse::<CommitMessage>() {
+        Ok(message) => {
+            let start = lines
+                .iter()
+                .skip(2)
+                .take_while(|(_, line)| line.trim().is_empty())
+                .count()
+                + 2;
+            let body = lines.get(start..).unwrap_or_default();
+            (
+                Some(message.header.clone()),
+                &body[..message.body_line_count().min(body.len())],
+            )
+        }
+        Err(error) => {
+            let rule = match error.kind {
+                ParseErrorKind::MissingBlankLine => "body-leading-blank",
+                ParseErrorKind::EmptyDescription => "summary-empty",
+                _ => "header-format",
+            };
+            diagnostics.push(at(
+                number(error.line - 1),
+                error.column,
+                rule,
+                error.kind.to_string(),
+            ));
+            // Without the blank line, the rest of the message is still checked as a body.
+            let (header, rest) = text.split_once('\n').unwrap_or_default();
+            match format!("{header}\n\n{rest}").parse::<CommitMessage>() {
+                Ok(message) if error.kind == ParseErrorKind::MissingBlankLine => {
+                    let body = lines.get(1..).unwrap_or_default();
+                    let count = message.body_line_count().min(body.len());
+                    (Some(message.header), &body[..count])
+                }
+                _ => (None, &[][..]),
+            }
+        }
+    };
+
+    if let Some(header) = header {
+        let line = number(0);
+        if let Some(message) = invalid(commit_type_validator(config)(&header.commit_type)) {
+            diagnostics.push(at(line, 1, "type", message));
+        }
+        let scope = header.scope.as_deref().unwrap_or_default();
+        if let Some(message) = invalid(validate_scope(scope, config)) {
+            let column = header.commit_type.chars().count() + 1;
+            let column = if header.scope.is_some() {
+                column + 1
+            } else {
+                column
+            };
+            diagnostics.push(at(line, column, "scope", message));
+        }
+        check_summary(
+            &header.description,
+            line,
+            header.description_column(),
+            spelling,
+            &mut diagnostics,
+        );
+    }
+    for &(line, text) in body {
+        if let Some(message) = invalid(validate_body_line_length(text)) {
+            diagnostics.push(at(
+                line,
+                BODY_MAX_LINE_LENGTH + 1,
+                "body-line-length",
+                message,
+            ));
+        }
+        if spelling {
+            check_spelling(text, line, 1, &mut diagnostics);
+        }
+    }
+    diagnostics
+}
+
+///
+/// Reads a commit message file, as given by git to the `commit-msg` hook, and checks it.
+///
+/// # Errors
+/// - If the file cannot be read.
+///
+pub fn check_message_file(path: &Path, config: &Config) -> Result<Vec<Diagnostic>, Error> {
+    Ok(check_message(&read_to_string(path)?, config))
+}
+
+fn check_summary(
+    summary: &str,
+    line: usize,
+    start: usize,
+    spelling: bool,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    if let Some(message) = invalid(validate_not_empty(summary)) {
+        diagnostics.push(at(line, start, "summary-empty", message));
+        return;
+    }
+    if let Some(message) = invalid(validate_summary_length(summary)) {
+        diagnostics.push(at(
+            line,
+            start + SUMMARY_MAX_LENGTH,
+            "summary-length",
+            message,
+        ));
+    }
+    if let Some(message) = invalid(validate_summary_punctuation(summary)) {
+        let column = start + summary.trim_end().chars().count() - 1;
+        diagnostics.push(at(line, column, "summary-punctuation", message));
+    }
+    if spelling {
+        check_spelling(summary, line, start, diagnostics);
+    }
+}
+
+fn check_spelling(text: &str, line: usize, start: usize, diagnostics: &mut Vec<Diagnostic>) {
+    for (column, (i, c)) in (start..).zip(text.char_indices()) {
+        let word_start = i == 0 || text[..i].ends_with(char::is_whitespace);
+        if word_start && !c.is_whitespace() {
+            let word = text[i..].split_whitespace().next().unwrap_or_default();
+            if let Some(message) = invalid(validate_spelling(word)) {
+                diagnostics.push(at(line, column, "spelling", message));
+            }
+        }
+    }
+}
+
+/// Returns the numbered lines git keeps in a commit message, without leading blank lines.
+fn meaningful_lines(content: &str) -> Vec<(usize, &str)> {
+    content
+        .lines()
+        .enumerate()
+        .take_while(|(_, line)| *line != SCISSORS)
+        .filter(|(_, line)| !line.starts_with('#'))
+        .map(|(i, line)| (i + 1, line))
+        .skip_while(|(_, line)| line.trim().is_empty())
+        .collect()
+}
+
+fn at(line: usize, column: usize, rule: &'static str, message: String) -> Diagnostic {
+    Diagnostic {
+        line,
+        column,
+        rule,
+        message,
+    }
+}
+
+fn invalid(result: Result<Validation, CustomUserError>) -> Option<String> {
+    match result {
+        Ok(Validation::Valid) => None,
+        Ok(Validation::Invalid(ErrorMessage::Custom(message))) => Some(message),
+        Ok(Validation::Invalid(ErrorMessage::Default)) => Some(String::from("Invalid input")),
+        Err(e) => Some(e.to_string()),
+    }
+}
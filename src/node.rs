@@ -0,0 +1,244 @@
+use crate::hooks::Hook;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::fs::read_to_string;
+use std::io::{Error, ErrorKind};
+use std::path::Path;
+
+/// The manifest of a JavaScript project.
+pub const PACKAGE_JSON: &str = "package.json";
+
+///
+/// The package manager of a JavaScript project, which runs its scripts and audits its
+/// dependencies.
+///
+/// # Example
+/// ```rust
+/// use breathes::node::PackageManager;
+///
+/// assert_eq!(PackageManager::Pnpm.run("test"), "pnpm run test");
+/// assert_eq!(PackageManager::Bun.exec("tsc --noEmit"), "bunx tsc --noEmit");
+/// assert_eq!(PackageManager::YarnBerry.audit(), "yarn npm audit");
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PackageManager {
+    /// npm, used when nothing points to another package manager.
+    #[default]
+    Npm,
+    /// pnpm, found from `pnpm-lock.yaml`.
+    Pnpm,
+    /// Yarn 1, found from `yarn.lock`.
+    Yarn,
+    /// Yarn 2 and later, found from `.yarnrc.yml` or the `packageManager` field.
+    YarnBerry,
+    /// Bun, found from `bun.lock` or `bun.lockb`.
+    Bun,
+}
+
+impl PackageManager {
+    ///
+    /// Detects the package manager of the project in `dir`, from the `packageManager` field
+    /// of its manifest, then from its lockfile.
+    ///
+    #[must_use]
+    pub fn detect(dir: &Path, package_manager: Option<&str>) -> Self {
+        let yarn_berry = dir.join(".yarnrc.yml").is_file();
+        if let Some((name, version)) = package_manager.and_then(|field| field.split_once('@')) {
+            match name {
+                "npm" => return Self::Npm,
+                "pnpm" => return Self::Pnpm,
+                "bun" => return Self::Bun,
+                "yarn" if version.starts_with("1.") && !yarn_berry => return Self::Yarn,
+                "yarn" => return Self::YarnBerry,
+                _ => {}
+            }
+        }
+        if dir.join("pnpm-lock.yaml").is_file() {
+            Self::Pnpm
+        } else if dir.join("yarn.lock").is_file() {
+            if yarn_berry {
+                Self::YarnBerry
+            } else {
+                Self::Yarn
+            }
+        } else if dir.join("bun.lock").is_file() || dir.join("bun.lockb").is_file() {
+            Self::Bun
+        } else {
+            Self::Npm
+        }
+    }
+
+    ///
+    /// Returns the command running the script `script` of the manifest.
+    ///
+    #[must_use]
+    pub fn run(self, script: &str) -> String {
+        format!("{self} run {script}")
+    }
+
+    ///
+    /// Returns the command running `command` from the binaries of the dependencies, as
+    /// `npx` does.
+    ///
+    #[must_use]
+    pub fn exec(self, command: &str) -> String {
+        match self {
+            Self::Npm => format!("npx {command}"),
+            Self::Pnpm => format!("pnpm exec {command}"),
+            Self::Yarn | Self::YarnBerry => format!("yarn {command}"),
+            Self::Bun => format!("bunx {command}"),
+        }
+    }
+
+    ///
+    /// Returns the command listing the outdated dependencies, if the package manager has one.
+    ///
+    #[must_use]
+    pub fn outdated(self) -> Option<String> {
+        match self {
+            Self::YarnBerry => None,
+            _ => Some(format!("{self} outdated")),
+        }
+    }
+
+    ///
+    /// Returns the command auditing the dependencies for known vulnerabilities.
+    ///
+    #[must_use]
+    pub fn audit(self) -> String {
+        match self {
+            Self::YarnBerry => "yarn npm audit".to_string(),
+            _ => format!("{self} audit"),
+        }
+    }
+}
+
+impl Display for PackageManager {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Npm => write!(f, "npm"),
+            Self::Pnpm => write!(f, "pnpm"),
+            Self::Yarn | Self::YarnBerry => write!(f, "yarn"),
+            Self::Bun => write!(f, "bun"),
+        }
+    }
+}
+
+/// The fields of `package.json` read by breathes.
+#[derive(Deserialize)]
+struct Manifest {
+    #[serde(default)]
+    scripts: BTreeMap<String, serde_json::Value>,
+    #[serde(rename = "packageManager")]
+    package_manager: Option<String>,
+}
+
+///
+/// A JavaScript project: its package manager and the scripts declared in its manifest.
+///
+/// * `manager`: The package manager of the project.
+/// * `scripts`: The names of the scripts of `package.json`, sorted.
+///
+/// # Example
+/// ```rust
+/// use breathes::hooks::{Hook, Language};
+/// use breathes::node::{Package, PackageManager};
+///
+/// let package = Package {
+///     manager: PackageManager::Pnpm,
+///     scripts: vec![String::from("test")],
+/// };
+/// let mut hooks = Hook::get(Language::Javascript);
+/// package.adapt(&mut hooks);
+/// let commands: Vec<&str> = hooks.iter().map(|hook| hook.command.as_ref()).collect();
+/// assert_eq!(commands, ["pnpm outdated", "pnpm run test", "pnpm audit"]);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Package {
+    pub manager: PackageManager,
+    pub scripts: Vec<String>,
+}
+
+impl Package {
+    ///
+    /// Reads the manifest of the project in `dir` and detects its package manager.
+    ///
+    /// # Errors
+    /// - If `package.json` cannot be read or is not a valid manifest.
+    ///
+    pub fn read(dir: &Path) -> Result<Self, Error> {
+        let path = dir.join(PACKAGE_JSON);
+        let manifest: Manifest = serde_json::from_str(&read_to_string(&path)?).map_err(|e| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("invalid {}: {e}", path.display()),
+            )
+        })?;
+        Ok(Self {
+            manager: PackageManager::detect(dir, manifest.package_manager.as_deref()),
+            scripts: manifest.scripts.into_keys().collect(),
+        })
+    }
+
+    ///
+    /// Returns `true` if the manifest declares the script `name`.
+    ///
+    #[must_use]
+    pub fn has_script(&self, name: &str) -> bool {
+        self.scripts.iter().any(|script| script == name)
+    }
+
+    ///
+    /// Adapts the built-in JavaScript and TypeScript `hooks` to this project.
+    ///
+    /// The commands use the package manager of the project instead of npm, and the hooks
+    /// running a script which the manifest does not declare, such as `lint`, are removed.
+    ///
+    pub fn adapt(&self, hooks: &mut Vec<Hook>) {
+        hooks.retain_mut(|hook| {
+            let command = match hook.id.as_ref() {
+                "javascript-outdated" => self.manager.outdated(),
+                "javascript-audit" => Some(self.manager.audit()),
+                "javascript-test" => self.has_script("test").then(|| self.manager.run("test")),
+                "javascript-lint" => self.has_script("lint").then(|| self.manager.run("lint")),
+                _ => match hook.command.strip_prefix("npx ") {
+                    Some(command) => Some(self.manager.exec(command)),
+                    None => Some(hook.command.to_string()),
+                },
+            };
+            command.is_some_and(|command| {
+                hook.command = command.into();
+                true
+            })
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Package, PackageManager};
+    use crate::testing::Fixture;
+
+    #[test]
+    fn read_the_package_manager_and_scripts_of_the_manifest() {
+        let fixture = Fixture::new("package");
+        fixture.write(
+            "package.json",
+            r#"{"packageManager": "pnpm@9.1.0", "scripts": {"test": "vitest run", "build": "vite"}}"#,
+        );
+
+        let package = Package::read(fixture.path()).unwrap();
+        assert_eq!(package.manager, PackageManager::Pnpm);
+        assert_eq!(package.scripts, ["build", "test"]);
+    }
+
+    #[test]
+    fn read_a_missing_or_invalid_manifest_fails() {
+        let fixture = Fixture::new("package-invalid");
+        assert!(Package::read(fixture.path()).is_err());
+
+        fixture.write("package.json", "{");
+        assert!(Package::read(fixture.path()).is_err());
+    }
+}
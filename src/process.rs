@@ -0,0 +1,218 @@
+use crate::snapshot::restore_interrupted;
+use std::io::{Error, ErrorKind};
+use std::process::{Child, Command, ExitStatus, Stdio, exit};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, MutexGuard, Once, PoisonError};
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+/// How often a running hook is polled while waiting for its deadline or a cancellation.
+pub const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// How long an interrupted run waits for the killed commands to be reaped before exiting.
+pub const INTERRUPT_GRACE: Duration = Duration::from_secs(5);
+
+/// The ids of the commands started by [`spawn_group`] and not reaped yet.
+static RUNNING: Mutex<Vec<u32>> = Mutex::new(Vec::new());
+
+/// Set once the run is interrupted with Ctrl-C or `SIGTERM`.
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+///
+/// Starts `cmd` as the leader of a new process group, so that [`kill_tree`] can stop the
+/// command together with every process it started.
+///
+/// On Windows, the processes are found through their parent instead, so the command is
+/// started as is. The standard input is closed: a hook in a background process group
+/// cannot read from the terminal.
+///
+/// Since the group does not receive the Ctrl-C of the terminal, the command is remembered
+/// until it is reaped, and killed by [`interrupt`] when the run is interrupted.
+///
+/// # Errors
+/// - If the command cannot be started.
+/// - If the run is interrupted.
+///
+pub fn spawn_group(cmd: &mut Command) -> Result<Child, Error> {
+    handle_interruptions();
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0);
+    }
+    let mut running = running();
+    if interrupted() {
+        return Err(Error::new(
+            ErrorKind::Interrupted,
+            "the run was interrupted",
+        ));
+    }
+    let child = cmd.stdin(Stdio::null()).spawn()?;
+    running.push(child.id());
+    Ok(child)
+}
+
+///
+/// How a command started by [`spawn_group`] ended, with its exit status.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Waited {
+    /// The command exited by itself.
+    Exited(ExitStatus),
+    /// The command outlived its timeout and was killed.
+    TimedOut(ExitStatus),
+    /// The run was cancelled or interrupted, and the command was killed.
+    Cancelled(ExitStatus),
+}
+
+///
+/// Waits for `child` to exit, killing it with [`kill_tree`] when `timeout` elapses or as
+/// soon as `cancelled` returns `true`.
+///
+/// # Errors
+/// - If the status of the child cannot be read.
+///
+/// # Example
+/// ```rust
+/// use breathes::process::{Waited, spawn_group, wait_or_kill};
+/// use std::process::Command;
+/// use std::time::Duration;
+///
+/// # #[cfg(unix)]
+/// # {
+/// let mut child = spawn_group(Command::new("sh").args(["-c", "sleep 10"])).unwrap();
+/// let timeout = Some(Duration::from_millis(100));
+/// let waited = wait_or_kill(&mut child, timeout, || false).unwrap();
+/// assert!(matches!(waited, Waited::TimedOut(_)));
+///
+/// let mut child = spawn_group(&mut Command::new("true")).unwrap();
+/// let waited = wait_or_kill(&mut child, None, || false).unwrap();
+/// assert!(matches!(waited, Waited::Exited(status) if status.success()));
+/// # }
+/// ```
+pub fn wait_or_kill(
+    child: &mut Child,
+    timeout: Option<Duration>,
+    cancelled: impl Fn() -> bool,
+) -> Result<Waited, Error> {
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+    loop {
+        if let Some(status) = try_reap(child)? {
+            if interrupted() {
+                return Ok(Waited::Cancelled(status));
+            }
+            return Ok(Waited::Exited(status));
+        }
+        if cancelled() {
+            return Ok(Waited::Cancelled(kill_tree(child)?));
+        }
+        let now = Instant::now();
+        match deadline {
+            Some(deadline) if now >= deadline => {
+                return Ok(Waited::TimedOut(kill_tree(child)?));
+            }
+            Some(deadline) => sleep(POLL_INTERVAL.min(deadline - now)),
+            None => sleep(POLL_INTERVAL),
+        }
+    }
+}
+
+///
+/// Kills `child` and every process it started, then reaps it.
+///
+/// On unix, the whole process group created by [`spawn_group`] receives `SIGKILL`. On
+/// Windows, `taskkill /T` stops the process tree.
+///
+/// # Errors
+/// - If the child cannot be reaped.
+///
+pub fn kill_tree(child: &mut Child) -> Result<ExitStatus, Error> {
+    let mut running = running();
+    kill_group(child.id());
+    // The child itself is killed even if the group could not be.
+    let _ = child.kill();
+    let status = child.wait();
+    running.retain(|id| *id != child.id());
+    status
+}
+
+///
+/// Returns `true` once the run is interrupted with Ctrl-C or `SIGTERM`.
+///
+#[must_use]
+pub fn interrupted() -> bool {
+    INTERRUPTED.load(Ordering::SeqCst)
+}
+
+///
+/// Interrupts the run: no command starts anymore, and every command started by
+/// [`spawn_group`] is killed with its process tree. Waits up to [`INTERRUPT_GRACE`] for
+/// them to be reaped by the threads waiting for them.
+///
+pub fn interrupt() {
+    {
+        let running = running();
+        INTERRUPTED.store(true, Ordering::SeqCst);
+        for id in running.iter() {
+            kill_group(*id);
+        }
+    }
+    let deadline = Instant::now() + INTERRUPT_GRACE;
+    while !running().is_empty() && Instant::now() < deadline {
+        sleep(POLL_INTERVAL);
+    }
+}
+
+///
+/// Installs, once, the handler of Ctrl-C and `SIGTERM`: it [`interrupt`]s the run, restores
+/// the working tree of the snapshot being checked once the hooks are killed, then exits.
+///
+pub(crate) fn handle_interruptions() {
+    static HANDLER: Once = Once::new();
+    HANDLER.call_once(|| {
+        // Another handler may already be installed by the application: the snapshot is
+        // still restored when dropped.
+        let _ = ctrlc::set_handler(|| {
+            interrupt();
+            restore_interrupted();
+            exit(130);
+        });
+    });
+}
+
+/// Reaps `child` if it has exited. The lock keeps [`interrupt`] from killing a process
+/// group whose id was already released.
+fn try_reap(child: &mut Child) -> Result<Option<ExitStatus>, Error> {
+    let mut running = running();
+    let status = child.try_wait()?;
+    if status.is_some() {
+        running.retain(|id| *id != child.id());
+    }
+    Ok(status)
+}
+
+fn running() -> MutexGuard<'static, Vec<u32>> {
+    RUNNING.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Kills the process group led by the unreaped process `id`, or its process tree on Windows.
+fn kill_group(id: u32) {
+    #[cfg(unix)]
+    if let Ok(pid) = libc::pid_t::try_from(id) {
+        // SAFETY: `kill` has no memory safety requirements; the negative pid targets the
+        // process group led by the child, which is not reaped yet.
+        unsafe {
+            libc::kill(-pid, libc::SIGKILL);
+        }
+    }
+    #[cfg(windows)]
+    {
+        let _ = Command::new("taskkill")
+            .args(["/T", "/F", "/PID", &id.to_string()])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status();
+    }
+    #[cfg(not(any(unix, windows)))]
+    let _ = id;
+}
@@ -0,0 +1,542 @@
+use crate::hooks::Language;
+use ignore::WalkBuilder;
+use serde::Serialize;
+use std::fmt::{Display, Formatter};
+use std::fs::read_dir;
+use std::io::Error;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+///
+/// A project of the repository: a directory holding the manifest of a language.
+///
+/// * `language`: The language of the project.
+/// * `root`: The directory of the project, relative to the directory searched, `.` for the
+///   top-level project. Its hooks run in this directory.
+///
+/// # Example
+/// ```rust
+/// use breathes::hooks::Language;
+/// use breathes::projects::Project;
+///
+/// assert_eq!(Project::new(Language::Rust, ".").to_string(), "Rust");
+/// assert_eq!(Project::new(Language::Go, "backend").to_string(), "Go (backend)");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize)]
+pub struct Project {
+    pub language: Language,
+    pub root: PathBuf,
+}
+
+impl Project {
+    ///
+    /// Creates the project of `language` rooted at `root`.
+    ///
+    #[must_use]
+    pub fn new(language: Language, root: impl Into<PathBuf>) -> Self {
+        Self {
+            language,
+            root: root.into(),
+        }
+    }
+
+    ///
+    /// Returns `true` for the project at the top of the directory searched.
+    ///
+    #[must_use]
+    pub fn is_top_level(&self) -> bool {
+        is_top_level(&self.root)
+    }
+}
+
+impl Display for Project {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.is_top_level() {
+            write!(f, "{}", self.language)
+        } else {
+            write!(f, "{} ({})", self.language, self.root.display())
+        }
+    }
+}
+
+///
+/// Returns `true` if `root` is the top of the directory searched.
+///
+#[must_use]
+pub fn is_top_level(root: &Path) -> bool {
+    root.as_os_str().is_empty() || root == Path::new(".")
+}
+
+///
+/// The kind of file suggesting a language.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SignalKind {
+    /// A manifest describing a project, such as `Cargo.toml` or `pyproject.toml`.
+    Manifest,
+    /// A lockfile written by a package manager, such as `poetry.lock`.
+    Lockfile,
+    /// Source files with an extension of the language, in any case, at least
+    /// [`EXTENSION_THRESHOLD`] of them.
+    Extension,
+}
+
+///
+/// A file pattern suggesting that a directory holds a project of a language.
+///
+/// * `language`: The language suggested.
+/// * `kind`: The kind of file, which sets the confidence of the detection.
+/// * `pattern`: The file name, or a `*` followed by its end, such as `*.csproj`.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Signal {
+    pub language: Language,
+    pub kind: SignalKind,
+    pub pattern: &'static str,
+}
+
+const fn signal(language: Language, kind: SignalKind, pattern: &'static str) -> Signal {
+    Signal {
+        language,
+        kind,
+        pattern,
+    }
+}
+
+/// The number of source files of a language a directory must hold to suggest it.
+pub const EXTENSION_THRESHOLD: usize = 3;
+
+///
+/// Every signal looked for in a directory, grouped by language in the order of
+/// [`LANGUAGES`](crate::hooks::LANGUAGES).
+///
+pub const SIGNALS: &[Signal] = &[
+    signal(Language::Rust, SignalKind::Manifest, "Cargo.toml"),
+    signal(Language::Rust, SignalKind::Lockfile, "Cargo.lock"),
+    signal(Language::Rust, SignalKind::Extension, "*.rs"),
+    signal(Language::Typescript, SignalKind::Manifest, "tsconfig.json"),
+    signal(Language::Typescript, SignalKind::Extension, "*.ts"),
+    signal(Language::Haskell, SignalKind::Manifest, "*.cabal"),
+    signal(Language::Haskell, SignalKind::Manifest, "cabal.project"),
+    signal(Language::Haskell, SignalKind::Manifest, "stack.yaml"),
+    signal(Language::Haskell, SignalKind::Lockfile, "stack.yaml.lock"),
+    signal(Language::Haskell, SignalKind::Extension, "*.hs"),
+    signal(Language::Javascript, SignalKind::Manifest, "package.json"),
+    signal(
+        Language::Javascript,
+        SignalKind::Lockfile,
+        "package-lock.json",
+    ),
+    signal(Language::Javascript, SignalKind::Lockfile, "yarn.lock"),
+    signal(Language::Javascript, SignalKind::Lockfile, "pnpm-lock.yaml"),
+    signal(Language::Javascript, SignalKind::Lockfile, "bun.lock"),
+    signal(Language::Javascript, SignalKind::Lockfile, "bun.lockb"),
+    signal(Language::Javascript, SignalKind::Extension, "*.js"),
+    signal(Language::CSharp, SignalKind::Manifest, "*.csproj"),
+    signal(Language::CSharp, SignalKind::Manifest, "*.sln"),
+    signal(Language::CSharp, SignalKind::Lockfile, "packages.lock.json"),
+    signal(Language::CSharp, SignalKind::Extension, "*.cs"),
+    signal(Language::Maven, SignalKind::Manifest, "pom.xml"),
+    signal(Language::Go, SignalKind::Manifest, "go.mod"),
+    signal(Language::Go, SignalKind::Manifest, "go.work"),
+    signal(Language::Go, SignalKind::Lockfile, "go.sum"),
+    signal(Language::Go, SignalKind::Lockfile, "go.work.sum"),
+    signal(Language::Go, SignalKind::Extension, "*.go"),
+    signal(Language::Ruby, SignalKind::Manifest, "Gemfile"),
+    signal(Language::Ruby, SignalKind::Manifest, "*.gemspec"),
+    signal(Language::Ruby, SignalKind::Lockfile, "Gemfile.lock"),
+    signal(Language::Ruby, SignalKind::Extension, "*.rb"),
+    signal(Language::Dart, SignalKind::Manifest, "pubspec.yaml"),
+    signal(Language::Dart, SignalKind::Lockfile, "pubspec.lock"),
+    signal(Language::Dart, SignalKind::Extension, "*.dart"),
+    signal(Language::Gradle, SignalKind::Manifest, "settings.gradle"),
+    signal(Language::Gradle, SignalKind::Manifest, "build.gradle"),
+    signal(
+        Language::Kotlin,
+        SignalKind::Manifest,
+        "settings.gradle.kts",
+    ),
+    signal(Language::Kotlin, SignalKind::Manifest, "build.gradle.kts"),
+    signal(Language::Kotlin, SignalKind::Extension, "*.kt"),
+    signal(Language::Swift, SignalKind::Manifest, "Package.swift"),
+    signal(Language::Swift, SignalKind::Lockfile, "Package.resolved"),
+    signal(Language::Swift, SignalKind::Extension, "*.swift"),
+    signal(Language::Php, SignalKind::Manifest, "composer.json"),
+    signal(Language::Php, SignalKind::Lockfile, "composer.lock"),
+    signal(Language::Php, SignalKind::Extension, "*.php"),
+    signal(Language::CMake, SignalKind::Manifest, "CMakeLists.txt"),
+    signal(Language::Elixir, SignalKind::Manifest, "mix.exs"),
+    signal(Language::Elixir, SignalKind::Lockfile, "mix.lock"),
+    signal(Language::Elixir, SignalKind::Extension, "*.ex"),
+    signal(Language::Python, SignalKind::Manifest, "pyproject.toml"),
+    signal(Language::Python, SignalKind::Manifest, "setup.py"),
+    signal(Language::Python, SignalKind::Manifest, "setup.cfg"),
+    signal(Language::Python, SignalKind::Manifest, "requirements.txt"),
+    signal(Language::Python, SignalKind::Manifest, "Pipfile"),
+    signal(Language::Python, SignalKind::Lockfile, "poetry.lock"),
+    signal(Language::Python, SignalKind::Lockfile, "Pipfile.lock"),
+    signal(Language::Python, SignalKind::Lockfile, "uv.lock"),
+    signal(Language::Python, SignalKind::Extension, "*.py"),
+    signal(Language::D, SignalKind::Manifest, "dub.json"),
+    signal(Language::D, SignalKind::Manifest, "dub.sdl"),
+    signal(Language::D, SignalKind::Lockfile, "dub.selections.json"),
+    signal(Language::D, SignalKind::Extension, "*.d"),
+    signal(Language::R, SignalKind::Manifest, "DESCRIPTION"),
+    signal(Language::R, SignalKind::Manifest, "*.Rproj"),
+    signal(Language::R, SignalKind::Lockfile, "renv.lock"),
+    signal(Language::R, SignalKind::Extension, "*.R"),
+];
+
+///
+/// How sure the detection of a language is.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Confidence {
+    /// Only source files suggest the language.
+    Low,
+    /// A lockfile suggests the language.
+    Medium,
+    /// A manifest declares a project of the language.
+    High,
+}
+
+impl Display for Confidence {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Low => write!(f, "low"),
+            Self::Medium => write!(f, "medium"),
+            Self::High => write!(f, "high"),
+        }
+    }
+}
+
+///
+/// A file, or a number of source files, which triggered the detection of a language.
+///
+/// * `kind`: The kind of signal matched.
+/// * `file`: The name of the file matched, or the pattern of the source files.
+/// * `count`: The number of files matched.
+///
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Evidence {
+    pub kind: SignalKind,
+    pub file: String,
+    pub count: usize,
+}
+
+impl Display for Evidence {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.kind {
+            SignalKind::Manifest => write!(f, "manifest {}", self.file),
+            SignalKind::Lockfile => write!(f, "lockfile {}", self.file),
+            SignalKind::Extension => write!(f, "{} {} files", self.count, self.file),
+        }
+    }
+}
+
+///
+/// A language detected in a directory, with how sure the detection is and why.
+///
+/// * `project`: The language and the directory it was detected in.
+/// * `confidence`: The confidence given by the strongest signal matched.
+/// * `evidence`: Every signal matched, strongest first.
+///
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Detection {
+    pub project: Project,
+    pub confidence: Confidence,
+    pub evidence: Vec<Evidence>,
+}
+
+impl Display for Detection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let evidence: Vec<String> = self.evidence.iter().map(ToString::to_string).collect();
+        write!(
+            f,
+            "{}: {} confidence ({})",
+            self.project,
+            self.confidence,
+            evidence.join(", ")
+        )
+    }
+}
+
+///
+/// Detects the languages of every directory under `dir`, from the [`SIGNALS`] found in it.
+///
+/// A directory holding a manifest of a language is detected with a high confidence, a
+/// lockfile alone gives a medium confidence and [`EXTENSION_THRESHOLD`] source files alone a
+/// low one. The walk follows the rules of [`discover`]: a language is not detected again
+/// inside a project of the same language.
+///
+#[must_use]
+pub fn detect_projects(dir: &Path) -> Vec<Detection> {
+    let walk = WalkBuilder::new(dir)
+        .require_git(false)
+        .filter_entry(|entry| entry.file_name() != "node_modules")
+        .sort_by_file_name(Ord::cmp)
+        .build();
+    let mut languages: Vec<Language> = Vec::new();
+    for signal in SIGNALS {
+        if !languages.contains(&signal.language) {
+            languages.push(signal.language);
+        }
+    }
+    let mut detections: Vec<Detection> = Vec::new();
+    for entry in walk.flatten() {
+        if !entry
+            .file_type()
+            .is_some_and(|file_type| file_type.is_dir())
+        {
+            continue;
+        }
+        let root = entry
+            .path()
+            .strip_prefix(dir)
+            .map_or_else(|_| entry.path().to_path_buf(), Path::to_path_buf);
+        let root = if root.as_os_str().is_empty() {
+            PathBuf::from(".")
+        } else {
+            root
+        };
+        let Ok(files) = read_dir(entry.path()) else {
+            continue;
+        };
+        let mut names: Vec<String> = files
+            .flatten()
+            .filter(|file| file.file_type().is_ok_and(|file_type| file_type.is_file()))
+            .map(|file| file.file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort_unstable();
+        for &language in &languages {
+            let nested = detections.iter().any(|detection| {
+                detection.project.language == language
+                    && detection.confidence > Confidence::Low
+                    && (detection.project.is_top_level()
+                        || root.starts_with(&detection.project.root))
+            });
+            if nested {
+                continue;
+            }
+            let mut evidence = evidence(language, &names);
+            evidence.sort_by_key(|evidence| confidence(evidence.kind));
+            evidence.reverse();
+            if let Some(strongest) = evidence.first() {
+                detections.push(Detection {
+                    project: Project::new(language, root.clone()),
+                    confidence: confidence(strongest.kind),
+                    evidence,
+                });
+            }
+        }
+    }
+    detections
+}
+
+///
+/// Finds every project under `dir`, such as `backend/Cargo.toml` and `frontend/package.json`
+/// in a monorepo.
+///
+/// The walk honors `.gitignore`, `.ignore` and the global git excludes, and skips hidden
+/// directories and `node_modules`. A project nested in a project of the same language, such
+/// as a member of a Cargo workspace, is left to the hooks of the enclosing project. The
+/// projects are returned from the top down, in the order of [`SIGNALS`] within a directory.
+///
+/// Only the detections of [`detect_projects`] with a manifest or a lockfile are projects:
+/// source files alone do not tell how to build and check them.
+///
+#[must_use]
+pub fn discover(dir: &Path) -> Vec<Project> {
+    detect_projects(dir)
+        .into_iter()
+        .filter(|detection| detection.confidence > Confidence::Low)
+        .map(|detection| detection.project)
+        .collect()
+}
+
+/// The signals of `language` matched by the file `names` of a directory.
+fn evidence(language: Language, names: &[String]) -> Vec<Evidence> {
+    let mut evidence = Vec::new();
+    for signal in SIGNALS.iter().filter(|signal| signal.language == language) {
+        let matched: Vec<&String> = names
+            .iter()
+            .filter(|name| match signal.kind {
+                // `*.R` sources are as often written `*.r`.
+                SignalKind::Extension => matches(
+                    &signal.pattern.to_ascii_lowercase(),
+                    &name.to_ascii_lowercase(),
+                ),
+                SignalKind::Manifest | SignalKind::Lockfile => matches(signal.pattern, name),
+            })
+            .collect();
+        match signal.kind {
+            SignalKind::Extension if matched.len() >= EXTENSION_THRESHOLD => {
+                evidence.push(Evidence {
+                    kind: signal.kind,
+                    file: signal.pattern.to_string(),
+                    count: matched.len(),
+                });
+            }
+            SignalKind::Extension => {}
+            SignalKind::Manifest | SignalKind::Lockfile => {
+                evidence.extend(matched.into_iter().map(|name| Evidence {
+                    kind: signal.kind,
+                    file: name.clone(),
+                    count: 1,
+                }));
+            }
+        }
+    }
+    evidence
+}
+
+const fn confidence(kind: SignalKind) -> Confidence {
+    match kind {
+        SignalKind::Manifest => Confidence::High,
+        SignalKind::Lockfile => Confidence::Medium,
+        SignalKind::Extension => Confidence::Low,
+    }
+}
+
+/// Whether the file `name` matches `pattern`, a file name or a `*` followed by its end.
+fn matches(pattern: &str, name: &str) -> bool {
+    pattern.strip_prefix('*').map_or(pattern == name, |end| {
+        name.ends_with(end) && name.len() > end.len()
+    })
+}
+
+///
+/// Returns the files staged for the next commit, relative to the current directory.
+///
+/// Staged files outside the current directory are left out.
+///
+/// # Errors
+/// - If git cannot be started, or fails, such as outside a repository.
+///
+pub fn staged_files() -> Result<Vec<PathBuf>, Error> {
+    let output = Command::new("git")
+        .args(["diff", "--cached", "--name-only", "-z", "--relative"])
+        .output()?;
+    if !output.status.success() {
+        return Err(Error::other(format!(
+            "git diff --cached failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(output
+        .stdout
+        .split(|byte| *byte == 0)
+        .filter(|file| !file.is_empty())
+        .map(|file| PathBuf::from(String::from_utf8_lossy(file).into_owned()))
+        .collect())
+}
+
+///
+/// Keeps the projects owning at least one of `files`.
+///
+/// A file belongs to the deepest project root containing it, so a change in `backend/`
+/// selects the projects of `backend` and not the top-level ones. Every project sharing
+/// that root is selected, whatever its language. Files outside every project select
+/// nothing.
+///
+/// # Example
+/// ```rust
+/// use breathes::hooks::Language;
+/// use breathes::projects::{Project, touched};
+/// use std::path::PathBuf;
+///
+/// let projects = [
+///     Project::new(Language::Javascript, "."),
+///     Project::new(Language::Rust, "backend"),
+///     Project::new(Language::Go, "tools"),
+/// ];
+/// let files = [PathBuf::from("backend/src/main.rs")];
+/// assert_eq!(touched(&projects, &files), [Project::new(Language::Rust, "backend")]);
+///
+/// let files = [PathBuf::from("README.md")];
+/// assert_eq!(touched(&projects, &files), [Project::new(Language::Javascript, ".")]);
+/// ```
+#[must_use]
+pub fn touched(projects: &[Project], files: &[PathBuf]) -> Vec<Project> {
+    let mut roots: Vec<&Path> = Vec::new();
+    for file in files {
+        let owner = projects
+            .iter()
+            .filter(|project| project.is_top_level() || file.starts_with(&project.root))
+            .max_by_key(|project| {
+                if project.is_top_level() {
+                    0
+                } else {
+                    project.root.components().count()
+                }
+            });
+        if let Some(owner) = owner
+            && !roots.contains(&owner.root.as_path())
+        {
+            roots.push(&owner.root);
+        }
+    }
+    projects
+        .iter()
+        .filter(|project| roots.contains(&project.root.as_path()))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Confidence, Project, detect_projects, discover};
+    use crate::hooks::Language;
+    use crate::testing::Fixture;
+
+    #[test]
+    fn detect_projects_from_manifests_lockfiles_and_sources() {
+        let fixture = Fixture::new("detect");
+        fixture
+            .write("pyproject.toml", "")
+            .write("poetry.lock", "")
+            .write("scripts/a.rb", "")
+            .write("scripts/b.rb", "")
+            .write("scripts/c.rb", "")
+            .write("stats/a.r", "")
+            .write("stats/b.R", "")
+            .write("stats/c.r", "");
+
+        let detections = detect_projects(fixture.path());
+        assert_eq!(detections[0].project.language, Language::Python);
+        assert_eq!(detections[0].confidence, Confidence::High);
+        assert_eq!(
+            detections[0].to_string(),
+            "Python: high confidence (manifest pyproject.toml, lockfile poetry.lock)"
+        );
+        assert_eq!(
+            detections[1].to_string(),
+            "Ruby (scripts): low confidence (3 *.rb files)"
+        );
+        assert_eq!(
+            detections[2].to_string(),
+            "R (stats): low confidence (3 *.R files)"
+        );
+    }
+
+    #[test]
+    fn discover_keeps_the_outermost_projects_and_honors_gitignore() {
+        let fixture = Fixture::new("discover");
+        fixture
+            .write(".gitignore", "vendored/\n")
+            .write("backend/Cargo.toml", "")
+            .write("backend/crates/core/Cargo.toml", "")
+            .write("frontend/package.json", "{}")
+            .write("vendored/go.mod", "");
+
+        assert_eq!(
+            discover(fixture.path()),
+            [
+                Project::new(Language::Rust, "backend"),
+                Project::new(Language::Javascript, "frontend"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,232 @@
+use crate::files::quote;
+use crate::hooks::Hook;
+use crate::tools::{find_executable, find_executable_in};
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+
+/// The directories searched for a virtual environment created inside the project.
+pub const VENV_DIRS: [&str; 2] = [".venv", "venv"];
+
+///
+/// The environment in which the Python tools of a project run.
+///
+/// # Example
+/// ```rust
+/// use breathes::python::Environment;
+///
+/// assert_eq!(Environment::Uv.exec("pytest"), "uv run pytest");
+/// assert_eq!(Environment::Uv.exec("pip list"), "uv pip list");
+/// assert_eq!(Environment::Poetry.exec("pip list"), "poetry run pip list");
+/// assert_eq!(Environment::System.exec("mypy ."), "mypy .");
+/// # #[cfg(unix)]
+/// assert_eq!(Environment::Venv(".venv".into()).exec("ruff check"), ".venv/bin/ruff check");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum Environment {
+    /// The tools found on the `PATH`.
+    #[default]
+    System,
+    /// A virtual environment in the project, such as `.venv`.
+    Venv(PathBuf),
+    /// The environment managed by Poetry, found from `poetry.lock` or `[tool.poetry]`.
+    Poetry,
+    /// The environment managed by uv, found from `uv.lock`.
+    Uv,
+    /// The environment managed by Pipenv, found from `Pipfile`.
+    Pipenv,
+}
+
+impl Environment {
+    ///
+    /// Detects the environment of the project in `dir`.
+    ///
+    #[must_use]
+    pub fn detect(dir: &Path, pyproject: Option<&toml::Table>) -> Self {
+        let poetry = pyproject
+            .and_then(|pyproject| pyproject.get("tool"))
+            .and_then(|tool| tool.get("poetry"))
+            .is_some();
+        if dir.join("uv.lock").is_file() {
+            Self::Uv
+        } else if poetry || dir.join("poetry.lock").is_file() {
+            Self::Poetry
+        } else if dir.join("Pipfile").is_file() {
+            Self::Pipenv
+        } else if let Some(venv) = VENV_DIRS
+            .into_iter()
+            .find(|venv| dir.join(venv).join("pyvenv.cfg").is_file())
+        {
+            Self::Venv(PathBuf::from(venv))
+        } else {
+            Self::System
+        }
+    }
+
+    ///
+    /// Returns `command`, whose first word is a tool, run inside the environment.
+    ///
+    /// The environments of uv have no `pip`: `pip` commands run with `uv pip` instead.
+    ///
+    #[must_use]
+    pub fn exec(&self, command: &str) -> String {
+        match self {
+            Self::System => command.to_string(),
+            Self::Venv(venv) => {
+                let (tool, args) = command.split_once(' ').unwrap_or((command, ""));
+                let tool = quote(&scripts(venv).join(tool));
+                if args.is_empty() {
+                    tool
+                } else {
+                    format!("{tool} {args}")
+                }
+            }
+            Self::Poetry => format!("poetry run {command}"),
+            Self::Uv => command.strip_prefix("pip ").map_or_else(
+                || format!("uv run {command}"),
+                |args| format!("uv pip {args}"),
+            ),
+            Self::Pipenv => format!("pipenv run {command}"),
+        }
+    }
+}
+
+///
+/// A Python project: its environment and the tools it uses among `pytest`, `ruff` and `mypy`.
+///
+/// A tool is used when it is configured, in `pyproject.toml`, `setup.cfg` or a file of its
+/// own such as `mypy.ini`, or when it is installed: in the virtual environment of the
+/// project, in the lockfile of Poetry, uv or Pipenv, or on the `PATH` for a project without
+/// an environment. An unreadable `pyproject.toml` configures nothing.
+///
+/// * `environment`: The environment in which the tools run.
+/// * `tools`: The tools used by the project.
+///
+/// # Example
+/// ```rust
+/// use breathes::hooks::{Hook, Language};
+/// use breathes::python::{Environment, Toolchain};
+///
+/// let toolchain = Toolchain {
+///     environment: Environment::Uv,
+///     tools: vec![String::from("pytest"), String::from("ruff")],
+/// };
+/// let mut hooks = Hook::get(Language::Python);
+/// toolchain.adapt(&mut hooks);
+/// let commands: Vec<&str> = hooks.iter().map(|hook| hook.command.as_ref()).collect();
+/// assert_eq!(
+///     commands,
+///     [
+///         "uv pip list --outdated",
+///         "uv run pip-audit",
+///         "uv run pytest",
+///         "uv run ruff check",
+///         "uv run ruff format --check",
+///     ]
+/// );
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Toolchain {
+    pub environment: Environment,
+    pub tools: Vec<String>,
+}
+
+impl Toolchain {
+    /// The tools looked for, with the files configuring them and their sections.
+    const TOOLS: [(&'static str, &'static [&'static str], &'static str); 3] = [
+        ("pytest", &["pytest.ini"], "[tool:pytest]"),
+        ("ruff", &["ruff.toml", ".ruff.toml"], ""),
+        ("mypy", &["mypy.ini", ".mypy.ini"], "[mypy]"),
+    ];
+
+    ///
+    /// Finds the environment and the tools of the project in `dir`.
+    ///
+    #[must_use]
+    pub fn read(dir: &Path) -> Self {
+        let pyproject = read_to_string(dir.join("pyproject.toml"))
+            .ok()
+            .and_then(|pyproject| pyproject.parse::<toml::Table>().ok());
+        let setup_cfg = read_to_string(dir.join("setup.cfg")).unwrap_or_default();
+        let environment = Environment::detect(dir, pyproject.as_ref());
+        let lockfile = ["uv.lock", "poetry.lock", "Pipfile.lock"]
+            .into_iter()
+            .filter_map(|lockfile| read_to_string(dir.join(lockfile)).ok())
+            .collect::<String>();
+        let tools = Self::TOOLS
+            .into_iter()
+            .filter(|(tool, files, section)| {
+                let configured = pyproject
+                    .as_ref()
+                    .and_then(|pyproject| pyproject.get("tool"))
+                    .and_then(|config| config.get(tool))
+                    .is_some()
+                    || files.iter().any(|file| dir.join(file).is_file())
+                    || (!section.is_empty()
+                        && setup_cfg.lines().any(|line| line.trim() == *section));
+                let installed = match &environment {
+                    Environment::System => find_executable(tool).is_some(),
+                    Environment::Venv(venv) => {
+                        find_executable_in(&dir.join(scripts(venv)), tool).is_some()
+                    }
+                    Environment::Poetry | Environment::Uv | Environment::Pipenv => {
+                        lockfile.contains(&format!("name = \"{tool}\""))
+                            || lockfile.contains(&format!("\"{tool}\": {{"))
+                    }
+                };
+                configured || installed
+            })
+            .map(|(tool, _, _)| tool.to_string())
+            .collect();
+        Self { environment, tools }
+    }
+
+    ///
+    /// Adapts the built-in Python `hooks` to this project.
+    ///
+    /// The hooks of the tools the project does not use are removed, and the others run in
+    /// its environment, like the hooks checking its packages.
+    ///
+    pub fn adapt(&self, hooks: &mut Vec<Hook>) {
+        hooks.retain_mut(|hook| {
+            let tool = match hook.id.as_ref() {
+                "python-test" => Some("pytest"),
+                "python-lint" | "python-format" => Some("ruff"),
+                "python-types" => Some("mypy"),
+                "python-outdated" | "python-audit" => None,
+                _ => return true,
+            };
+            if tool.is_some_and(|tool| !self.tools.iter().any(|used| used == tool)) {
+                return false;
+            }
+            hook.command = self.environment.exec(&hook.command).into();
+            true
+        });
+    }
+}
+
+/// The directory holding the executables of the virtual environment `venv`.
+pub(crate) fn scripts(venv: &Path) -> PathBuf {
+    if cfg!(windows) {
+        venv.join("Scripts")
+    } else {
+        venv.join("bin")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Environment, Toolchain};
+    use crate::testing::Fixture;
+
+    #[test]
+    fn read_the_environment_and_the_tools_of_the_project() {
+        let fixture = Fixture::new("toolchain");
+        fixture
+            .write("pyproject.toml", "[tool.ruff]\nline-length = 100\n")
+            .write("uv.lock", "[[package]]\nname = \"pytest\"\n");
+
+        let toolchain = Toolchain::read(fixture.path());
+        assert_eq!(toolchain.environment, Environment::Uv);
+        assert_eq!(toolchain.tools, ["pytest", "ruff"]);
+    }
+}
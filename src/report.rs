@@ -0,0 +1,196 @@
+use crate::hooks::{Language, OutputFormat};
+use crate::projects::Project;
+use serde::Serialize;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+use std::time::Duration;
+
+///
+/// The outcome of a single hook.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum HookStatus {
+    /// The command exited successfully.
+    Passed,
+    /// The command failed.
+    Failed,
+    /// The command failed, but the hook is `warn_only`.
+    Warned,
+    /// The command did not finish in time and was killed, with every process it started.
+    TimedOut,
+    /// The command did not run, because a hook it depends on did not pass or the run was
+    /// cancelled.
+    Skipped,
+    /// The command was killed because the run was cancelled after another hook failed, or
+    /// did not start because the run was interrupted.
+    Cancelled,
+    /// The command did not run, because a tool it needs is not installed.
+    ToolMissing,
+}
+
+impl HookStatus {
+    ///
+    /// Whether this outcome fails the run. A timeout or a missing tool fails the run even for
+    /// a `warn_only` hook.
+    ///
+    #[must_use]
+    pub const fn is_failure(self) -> bool {
+        matches!(self, Self::Failed | Self::TimedOut | Self::ToolMissing)
+    }
+}
+
+impl Display for HookStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Passed => write!(f, "passed"),
+            Self::Failed => write!(f, "failed"),
+            Self::Warned => write!(f, "warned"),
+            Self::TimedOut => write!(f, "timed out"),
+            Self::Skipped => write!(f, "skipped"),
+            Self::Cancelled => write!(f, "cancelled"),
+            Self::ToolMissing => write!(f, "tool missing"),
+        }
+    }
+}
+
+///
+/// What happened when a hook ran.
+///
+/// * `id`: The identifier of the hook.
+/// * `description`: The description of the hook.
+/// * `status`: The outcome of the hook.
+/// * `exit_code`: The exit code of the command, if it exited normally or could be started.
+/// * `signal`: The signal which terminated the command, on unix.
+/// * `duration`: The wall time taken by the command.
+/// * `stdout`: The file holding the standard output of the command.
+/// * `stderr`: The file holding the standard error of the command.
+/// * `message`: The success or failure message of the hook, or why the command could not start.
+/// * `output`: The format of the standard output of the command.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HookReport {
+    pub id: String,
+    pub description: String,
+    pub status: HookStatus,
+    pub exit_code: Option<i32>,
+    pub signal: Option<i32>,
+    pub duration: Duration,
+    pub stdout: PathBuf,
+    pub stderr: PathBuf,
+    pub message: String,
+    pub output: OutputFormat,
+}
+
+///
+/// The hooks run for one project.
+///
+/// * `language`: The language the hooks belong to.
+/// * `root`: The directory of the project the hooks ran in, `.` for the top-level project.
+/// * `hooks`: The report of each hook, in the order they ran.
+/// * `duration`: The wall time taken by the hooks of this language.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LanguageReport {
+    pub language: Language,
+    pub root: PathBuf,
+    pub hooks: Vec<HookReport>,
+    pub duration: Duration,
+}
+
+impl LanguageReport {
+    ///
+    /// Whether no hook of this language failed.
+    ///
+    #[must_use]
+    pub fn success(&self) -> bool {
+        !self.hooks.iter().any(|hook| hook.status.is_failure())
+    }
+
+    ///
+    /// Returns the project the hooks ran for.
+    ///
+    #[must_use]
+    pub fn project(&self) -> Project {
+        Project::new(self.language, self.root.clone())
+    }
+}
+
+///
+/// The outcome of a complete run, as returned by [`crate::hooks::run_hooks`].
+///
+/// * `languages`: The report of each detected project.
+/// * `duration`: The wall time of the whole run.
+///
+/// # Example
+/// ```rust
+/// use breathes::hooks::{Language, OutputFormat};
+/// use breathes::report::{HookReport, HookStatus, LanguageReport, RunReport};
+/// use std::time::Duration;
+///
+/// let hook = HookReport {
+///     id: "rust-fmt".into(),
+///     description: "Checks for formatting issues".into(),
+///     status: HookStatus::Warned,
+///     exit_code: Some(1),
+///     signal: None,
+///     duration: Duration::from_millis(300),
+///     stdout: "breathes/Rust/stdout/fmt.log".into(),
+///     stderr: "breathes/Rust/stderr/fmt.log".into(),
+///     message: "Code format standard not respected".into(),
+///     output: OutputFormat::Text,
+/// };
+/// let report = RunReport {
+///     languages: vec![LanguageReport {
+///         language: Language::Rust,
+///         root: ".".into(),
+///         hooks: vec![hook],
+///         duration: Duration::from_millis(300),
+///     }],
+///     duration: Duration::from_millis(310),
+/// };
+/// assert!(report.success());
+/// assert_eq!(report.count(HookStatus::Warned), 1);
+/// assert_eq!(report.failures().count(), 0);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RunReport {
+    pub languages: Vec<LanguageReport>,
+    pub duration: Duration,
+}
+
+impl RunReport {
+    ///
+    /// Whether no hook failed.
+    ///
+    #[must_use]
+    pub fn success(&self) -> bool {
+        self.languages.iter().all(LanguageReport::success)
+    }
+
+    ///
+    /// Returns every hook report, with the language it belongs to.
+    ///
+    pub fn hooks(&self) -> impl Iterator<Item = (Language, &HookReport)> {
+        self.languages
+            .iter()
+            .flat_map(|language| language.hooks.iter().map(|hook| (language.language, hook)))
+    }
+
+    ///
+    /// Returns the hooks which failed the run.
+    ///
+    pub fn failures(&self) -> impl Iterator<Item = (Language, &HookReport)> {
+        self.hooks().filter(|(_, hook)| hook.status.is_failure())
+    }
+
+    ///
+    /// Returns the number of hooks with the given status.
+    ///
+    #[must_use]
+    pub fn count(&self, status: HookStatus) -> usize {
+        self.hooks()
+            .filter(|(_, hook)| hook.status == status)
+            .count()
+    }
+}
@@ -0,0 +1,273 @@
+use crate::hooks::OutputFormat;
+use crate::projects::is_top_level;
+use crate::report::RunReport;
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::fmt::{Display, Formatter};
+use std::fs::read_to_string;
+use std::io::{Error, Write};
+use std::path::Path;
+
+/// The version of the SARIF specification written by [`write_sarif`].
+pub const SARIF_VERSION: &str = "2.1.0";
+
+/// The JSON schema of the SARIF documents written by [`write_sarif`].
+pub const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+///
+/// The severity of a [`Finding`], named as in SARIF.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Level {
+    /// A problem which fails the build.
+    Error,
+    /// A problem which does not fail the build by itself.
+    Warning,
+    /// Additional information.
+    Note,
+}
+
+impl Display for Level {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Error => write!(f, "error"),
+            Self::Warning => write!(f, "warning"),
+            Self::Note => write!(f, "note"),
+        }
+    }
+}
+
+///
+/// A problem reported by a tool at a location of the source code.
+///
+/// * `rule`: The identifier of the rule, such as `clippy::needless_return` or `TS2322`.
+/// * `level`: The severity of the problem.
+/// * `message`: The description of the problem.
+/// * `file`: The path of the file, relative to the directory the hook ran in.
+/// * `line`, `column`: The 1-based start of the problem.
+/// * `end_line`, `end_column`: The 1-based end of the problem, when known.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Finding {
+    pub rule: Option<String>,
+    pub level: Level,
+    pub message: String,
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+    pub end_line: Option<usize>,
+    pub end_column: Option<usize>,
+}
+
+///
+/// Extracts the findings from the standard output of a hook.
+///
+/// Text output has no findings. Lines which do not match the format are ignored, so the
+/// progress messages mixed with the findings are harmless. Repeated findings, such as a
+/// warning reported for both the library and its tests by cargo, are kept once.
+///
+/// # Example
+/// ```rust
+/// use breathes::hooks::OutputFormat;
+/// use breathes::sarif::{Level, parse_findings};
+///
+/// let findings = parse_findings(
+///     OutputFormat::Tsc,
+///     "src/main.ts(3,7): error TS2322: Type 'string' is not assignable to type 'number'.\n",
+/// );
+/// assert_eq!(findings.len(), 1);
+/// assert_eq!(findings[0].rule.as_deref(), Some("TS2322"));
+/// assert_eq!((findings[0].line, findings[0].column), (3, 7));
+/// assert_eq!(findings[0].level, Level::Error);
+/// ```
+#[must_use]
+pub fn parse_findings(format: OutputFormat, output: &str) -> Vec<Finding> {
+    let findings = match format {
+        OutputFormat::Text => Vec::new(),
+        OutputFormat::CargoJson => parse_cargo(output),
+        OutputFormat::Tsc => parse_tsc(output),
+    };
+    let mut unique: Vec<Finding> = Vec::with_capacity(findings.len());
+    for finding in findings {
+        if !unique.contains(&finding) {
+            unique.push(finding);
+        }
+    }
+    unique
+}
+
+///
+/// Writes the findings of the hooks of `report` as a SARIF 2.1 document.
+///
+/// Each hook with a machine-readable output is a `run`, whose tool is named after the id
+/// of the hook. Its findings are read back from the standard output log of the hook, and
+/// their files are made relative to the top of the repository. Hooks with a text output are
+/// left out.
+///
+/// # Errors
+/// - If `out` cannot be written.
+///
+/// # Example
+/// ```rust
+/// use breathes::report::RunReport;
+/// use breathes::sarif::write_sarif;
+///
+/// let mut sarif = Vec::new();
+/// write_sarif(&RunReport::default(), &mut sarif).unwrap();
+/// let sarif: serde_json::Value = serde_json::from_slice(&sarif).unwrap();
+/// assert_eq!(sarif["version"], "2.1.0");
+/// assert_eq!(sarif["runs"].as_array().map(Vec::len), Some(0));
+/// ```
+pub fn write_sarif(report: &RunReport, out: &mut impl Write) -> Result<(), Error> {
+    let runs: Vec<Value> = report
+        .languages
+        .iter()
+        .flat_map(|language| language.hooks.iter().map(|hook| (&language.root, hook)))
+        .filter(|(_, hook)| hook.output != OutputFormat::Text)
+        .map(|(root, hook)| {
+            let output = read_to_string(&hook.stdout).unwrap_or_default();
+            let findings = parse_findings(hook.output, &output);
+            let mut rules: Vec<&str> = findings
+                .iter()
+                .filter_map(|finding| finding.rule.as_deref())
+                .collect();
+            rules.sort_unstable();
+            rules.dedup();
+            json!({
+                "tool": {
+                    "driver": {
+                        "name": hook.id,
+                        "fullDescription": { "text": hook.description },
+                        "rules": rules.iter().map(|rule| json!({ "id": rule })).collect::<Vec<_>>(),
+                    }
+                },
+                "results": findings.iter().map(|finding| result(root, finding)).collect::<Vec<_>>(),
+            })
+        })
+        .collect();
+    let sarif = json!({
+        "$schema": SARIF_SCHEMA,
+        "version": SARIF_VERSION,
+        "runs": runs,
+    });
+    serde_json::to_writer_pretty(&mut *out, &sarif)?;
+    writeln!(out)
+}
+
+fn result(root: &Path, finding: &Finding) -> Value {
+    let file = if is_top_level(root) || Path::new(&finding.file).is_absolute() {
+        finding.file.clone()
+    } else {
+        root.join(&finding.file).to_string_lossy().into_owned()
+    };
+    let mut region = json!({
+        "startLine": finding.line,
+        "startColumn": finding.column,
+    });
+    if let Some(end_line) = finding.end_line {
+        region["endLine"] = json!(end_line);
+    }
+    if let Some(end_column) = finding.end_column {
+        region["endColumn"] = json!(end_column);
+    }
+    let mut result = json!({
+        "level": finding.level.to_string(),
+        "message": { "text": finding.message },
+        "locations": [{
+            "physicalLocation": {
+                "artifactLocation": {
+                    "uri": file.replace('\\', "/"),
+                    "uriBaseId": "%SRCROOT%",
+                },
+                "region": region,
+            }
+        }],
+    });
+    if let Some(rule) = &finding.rule {
+        result["ruleId"] = json!(rule);
+    }
+    result
+}
+
+#[derive(Deserialize)]
+struct CargoMessage {
+    reason: String,
+    message: Option<CargoDiagnostic>,
+}
+
+#[derive(Deserialize)]
+struct CargoDiagnostic {
+    message: String,
+    level: String,
+    code: Option<CargoCode>,
+    spans: Vec<CargoSpan>,
+}
+
+#[derive(Deserialize)]
+struct CargoCode {
+    code: String,
+}
+
+#[derive(Deserialize)]
+struct CargoSpan {
+    file_name: String,
+    line_start: usize,
+    line_end: usize,
+    column_start: usize,
+    column_end: usize,
+    is_primary: bool,
+}
+
+fn parse_cargo(output: &str) -> Vec<Finding> {
+    output
+        .lines()
+        .filter(|line| line.starts_with('{'))
+        .filter_map(|line| serde_json::from_str::<CargoMessage>(line).ok())
+        .filter(|message| message.reason == "compiler-message")
+        .filter_map(|message| {
+            let diagnostic = message.message?;
+            let span = diagnostic.spans.iter().find(|span| span.is_primary)?;
+            let level = match diagnostic.level.as_str() {
+                level if level.starts_with("error") => Level::Error,
+                "warning" => Level::Warning,
+                _ => Level::Note,
+            };
+            Some(Finding {
+                rule: diagnostic.code.map(|code| code.code),
+                level,
+                message: diagnostic.message,
+                file: span.file_name.clone(),
+                line: span.line_start,
+                column: span.column_start,
+                end_line: Some(span.line_end),
+                end_column: Some(span.column_end),
+            })
+        })
+        .collect()
+}
+
+fn parse_tsc(output: &str) -> Vec<Finding> {
+    let Ok(pattern) = Regex::new(r"^(.+)\((\d+),(\d+)\): (error|warning|message) (TS\d+): (.*)$")
+    else {
+        return Vec::new();
+    };
+    output
+        .lines()
+        .filter_map(|line| pattern.captures(line.trim_end()))
+        .map(|captures| Finding {
+            rule: Some(captures[5].to_string()),
+            level: match &captures[4] {
+                "error" => Level::Error,
+                "warning" => Level::Warning,
+                _ => Level::Note,
+            },
+            message: captures[6].to_string(),
+            file: captures[1].to_string(),
+            line: captures[2].parse().unwrap_or(1),
+            column: captures[3].parse().unwrap_or(1),
+            end_line: None,
+            end_column: None,
+        })
+        .collect()
+}
@@ -0,0 +1,391 @@
+use crate::hooks::Hook;
+use crate::process::interrupted;
+use crate::report::{HookReport, HookStatus};
+use crate::tools::MissingTools;
+use std::collections::HashMap;
+use std::num::NonZeroUsize;
+use std::sync::mpsc::channel;
+use std::sync::{Condvar, Mutex, OnceLock, PoisonError};
+use std::thread::{available_parallelism, scope};
+
+///
+/// The state of a run shared by every language: the number of hook commands allowed to run
+/// at the same time, whether the run stops at the first failure, and what happens to the
+/// hooks whose tool is not installed.
+///
+/// # Example
+/// ```rust
+/// use breathes::scheduler::Jobs;
+///
+/// assert_eq!(Jobs::new(4).limit(), 4);
+/// assert_eq!(Jobs::new(0).limit(), 1);
+/// assert!(Jobs::available().limit() >= 1);
+///
+/// let jobs = Jobs::new(2).with_fail_fast(true);
+/// assert!(jobs.fail_fast() && !jobs.is_cancelled());
+/// jobs.cancel("rust-clippy");
+/// jobs.cancel("rust-test");
+/// assert_eq!(jobs.cancelled_by(), Some("rust-clippy"));
+/// ```
+#[derive(Debug)]
+pub struct Jobs {
+    limit: usize,
+    free: Mutex<usize>,
+    freed: Condvar,
+    fail_fast: bool,
+    missing_tools: MissingTools,
+    cancelled: OnceLock<String>,
+}
+
+impl Jobs {
+    ///
+    /// Allows `limit` hooks to run at once, or one if `limit` is 0.
+    ///
+    #[must_use]
+    pub fn new(limit: usize) -> Self {
+        let limit = limit.max(1);
+        Self {
+            limit,
+            free: Mutex::new(limit),
+            freed: Condvar::new(),
+            fail_fast: false,
+            missing_tools: MissingTools::Fail,
+            cancelled: OnceLock::new(),
+        }
+    }
+
+    ///
+    /// Allows as many hooks to run at once as the machine has cores.
+    ///
+    #[must_use]
+    pub fn available() -> Self {
+        Self::new(available_parallelism().map_or(1, NonZeroUsize::get))
+    }
+
+    ///
+    /// Returns the number of hooks allowed to run at once.
+    ///
+    #[must_use]
+    pub const fn limit(&self) -> usize {
+        self.limit
+    }
+
+    ///
+    /// Stops the run at the first failure when `fail_fast` is set.
+    ///
+    #[must_use]
+    pub const fn with_fail_fast(mut self, fail_fast: bool) -> Self {
+        self.fail_fast = fail_fast;
+        self
+    }
+
+    ///
+    /// Returns `true` if the run stops at the first failure.
+    ///
+    #[must_use]
+    pub const fn fail_fast(&self) -> bool {
+        self.fail_fast
+    }
+
+    ///
+    /// Sets what happens to the hooks whose tool is not installed.
+    ///
+    #[must_use]
+    pub const fn with_missing_tools(mut self, missing_tools: MissingTools) -> Self {
+        self.missing_tools = missing_tools;
+        self
+    }
+
+    ///
+    /// Returns what happens to the hooks whose tool is not installed.
+    ///
+    #[must_use]
+    pub const fn missing_tools(&self) -> MissingTools {
+        self.missing_tools
+    }
+
+    ///
+    /// Cancels the run because the hook `id` failed: no hook starts anymore, and the running
+    /// ones are killed. Only the first cancellation is remembered.
+    ///
+    pub fn cancel(&self, id: &str) {
+        let _ = self.cancelled.set(id.to_string());
+        let _free = self.free.lock().unwrap_or_else(PoisonError::into_inner);
+        self.freed.notify_all();
+    }
+
+    ///
+    /// Returns `true` once the run is cancelled, or interrupted with Ctrl-C.
+    ///
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.get().is_some() || interrupted()
+    }
+
+    ///
+    /// Returns the id of the hook whose failure cancelled the run.
+    ///
+    #[must_use]
+    pub fn cancelled_by(&self) -> Option<&str> {
+        self.cancelled.get().map(String::as_str)
+    }
+
+    fn try_acquire(&self) -> bool {
+        let mut free = self.free.lock().unwrap_or_else(PoisonError::into_inner);
+        if *free == 0 {
+            return false;
+        }
+        *free -= 1;
+        true
+    }
+
+    fn acquire(&self) -> bool {
+        let mut free = self.free.lock().unwrap_or_else(PoisonError::into_inner);
+        while *free == 0 && !self.is_cancelled() {
+            free = self
+                .freed
+                .wait(free)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+        if self.is_cancelled() {
+            return false;
+        }
+        *free -= 1;
+        true
+    }
+
+    fn release(&self) {
+        *self.free.lock().unwrap_or_else(PoisonError::into_inner) += 1;
+        self.freed.notify_one();
+    }
+}
+
+///
+/// Runs `hooks` concurrently, as soon as their dependencies have finished.
+///
+/// A hook starts once every hook named in its `depends_on` has passed or warned, and is
+/// skipped if one of them did not. Dependencies absent from `hooks`, such as disabled
+/// hooks, are ignored. A hook which is not `parallel` runs alone. At most as many hooks
+/// as `jobs` allows run at the same time.
+///
+/// In fail-fast mode, the first failure cancels `jobs`: the hooks not started yet are
+/// skipped, in every language sharing `jobs`, and `run` is expected to stop the running ones.
+/// After an interruption, the hooks not started yet are cancelled instead.
+///
+/// `run` executes a hook and `skip` reports a hook which cannot run, with its status
+/// ([`HookStatus::Skipped`] or [`HookStatus::Cancelled`]) and the reason.
+/// The reports are returned in the order of `hooks`.
+///
+/// # Example
+/// ```rust
+/// use breathes::hooks::Hook;
+/// use breathes::report::{HookReport, HookStatus};
+/// use breathes::scheduler::{Jobs, schedule};
+/// use std::time::Duration;
+///
+/// let hook = |id: &'static str, depends_on: &[&'static str]| Hook {
+///     id: id.into(),
+///     depends_on: depends_on.iter().map(|id| (*id).into()).collect(),
+///     ..Hook::default()
+/// };
+/// let hooks = [hook("build", &[]), hook("test", &["build"]), hook("fmt", &[])];
+/// let report = |hook: &Hook, status: HookStatus, message: String| HookReport {
+///     id: hook.id.to_string(),
+///     description: hook.description.to_string(),
+///     status,
+///     exit_code: None,
+///     signal: None,
+///     duration: Duration::ZERO,
+///     stdout: "stdout.log".into(),
+///     stderr: "stderr.log".into(),
+///     message,
+///     output: hook.output,
+/// };
+/// let failing_build = |hook: &Hook| {
+///     let status = if hook.id == "build" { HookStatus::Failed } else { HookStatus::Passed };
+///     report(hook, status, String::new())
+/// };
+/// let reports = schedule(&hooks, &Jobs::new(2), failing_build, report);
+/// let statuses: Vec<HookStatus> = reports.iter().map(|report| report.status).collect();
+/// assert_eq!(statuses, [HookStatus::Failed, HookStatus::Skipped, HookStatus::Passed]);
+/// ```
+pub fn schedule(
+    hooks: &[Hook],
+    jobs: &Jobs,
+    run: impl Fn(&Hook) -> HookReport + Sync,
+    skip: impl Fn(&Hook, HookStatus, String) -> HookReport,
+) -> Vec<HookReport> {
+    let mut index: HashMap<&str, usize> = HashMap::new();
+    for (i, hook) in hooks.iter().enumerate() {
+        index.entry(hook.id.as_ref()).or_insert(i);
+    }
+    let dependencies: Vec<Vec<usize>> = hooks
+        .iter()
+        .enumerate()
+        .map(|(i, hook)| {
+            hook.depends_on
+                .iter()
+                .filter_map(|id| index.get(id.as_ref()).copied())
+                .filter(|dependency| *dependency != i)
+                .collect()
+        })
+        .collect();
+    let mut reports: Vec<Option<HookReport>> = hooks.iter().map(|_| None).collect();
+    let mut started = vec![false; hooks.len()];
+    let (sender, receiver) = channel();
+    let run = &run;
+
+    scope(|scope| {
+        let mut running = 0;
+        let mut exclusive = false;
+        loop {
+            // Skip the hooks whose dependencies did not pass, then the hooks depending on them.
+            let mut changed = true;
+            while changed {
+                changed = false;
+                for (i, hook) in hooks.iter().enumerate() {
+                    if started[i] {
+                        continue;
+                    }
+                    let failed = dependencies[i].iter().find(|dependency| {
+                        reports[**dependency]
+                            .as_ref()
+                            .is_some_and(|report| !passed(report.status))
+                    });
+                    if let Some(dependency) = failed {
+                        let reason = format!("`{}` did not pass", hooks[*dependency].id);
+                        reports[i] = Some(skip(hook, HookStatus::Skipped, reason));
+                        started[i] = true;
+                        changed = true;
+                    }
+                }
+            }
+
+            for (i, hook) in hooks.iter().enumerate() {
+                if exclusive || jobs.is_cancelled() {
+                    break;
+                }
+                let ready = dependencies[i]
+                    .iter()
+                    .all(|dependency| reports[*dependency].is_some());
+                if started[i] || !ready {
+                    continue;
+                }
+                if !hook.parallel && running > 0 {
+                    // It starts once the running hooks are done, the others may start now.
+                    continue;
+                }
+                if running == 0 {
+                    if !jobs.acquire() {
+                        break;
+                    }
+                } else if !jobs.try_acquire() {
+                    break;
+                }
+                started[i] = true;
+                running += 1;
+                exclusive = !hook.parallel;
+                let sender = sender.clone();
+                scope.spawn(move || {
+                    let _ = sender.send((i, run(hook)));
+                });
+            }
+
+            if jobs.is_cancelled() {
+                let (status, reason) = jobs.cancelled_by().map_or_else(
+                    || (HookStatus::Cancelled, String::from("interrupted")),
+                    |id| {
+                        (
+                            HookStatus::Skipped,
+                            format!("cancelled after `{id}` failed"),
+                        )
+                    },
+                );
+                for (i, hook) in hooks.iter().enumerate() {
+                    if !started[i] {
+                        reports[i] = Some(skip(hook, status, reason.clone()));
+                        started[i] = true;
+                    }
+                }
+            }
+            if running == 0 {
+                // Nothing runs and nothing can start: the remaining hooks depend on each other.
+                for (i, hook) in hooks.iter().enumerate() {
+                    if !started[i] {
+                        let reason = String::from("dependency cycle");
+                        reports[i] = Some(skip(hook, HookStatus::Skipped, reason));
+                    }
+                }
+                break;
+            }
+            let Ok((i, report)) = receiver.recv() else {
+                break;
+            };
+            jobs.release();
+            running -= 1;
+            if !hooks[i].parallel {
+                exclusive = false;
+            }
+            if jobs.fail_fast() && report.status.is_failure() {
+                jobs.cancel(&report.id);
+            }
+            reports[i] = Some(report);
+        }
+    });
+    reports.into_iter().flatten().collect()
+}
+
+const fn passed(status: HookStatus) -> bool {
+    matches!(status, HookStatus::Passed | HookStatus::Warned)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Jobs, schedule};
+    use crate::hooks::Hook;
+    use crate::report::{HookReport, HookStatus};
+    use std::sync::Mutex;
+    use std::thread::sleep;
+    use std::time::Duration;
+
+    fn report(hook: &Hook, status: HookStatus, message: String) -> HookReport {
+        HookReport {
+            id: hook.id.to_string(),
+            description: hook.description.to_string(),
+            status,
+            exit_code: None,
+            signal: None,
+            duration: Duration::ZERO,
+            stdout: "stdout.log".into(),
+            stderr: "stderr.log".into(),
+            message,
+            output: hook.output,
+        }
+    }
+
+    #[test]
+    fn waiting_exclusive_hook_lets_the_others_start() {
+        let hook = |id: &'static str, parallel: bool| Hook {
+            id: id.into(),
+            parallel,
+            ..Hook::default()
+        };
+        let hooks = [hook("build", true), hook("check", false), hook("fmt", true)];
+        let started = Mutex::new(Vec::new());
+        schedule(
+            &hooks,
+            &Jobs::new(3),
+            |hook| {
+                started.lock().unwrap().push(hook.id.to_string());
+                if hook.id == "build" {
+                    sleep(Duration::from_millis(100));
+                }
+                report(hook, HookStatus::Passed, String::new())
+            },
+            report,
+        );
+        let started = started.into_inner().unwrap();
+        assert_eq!(started.len(), 3);
+        assert_eq!(started.last().map(String::as_str), Some("check"));
+    }
+}
@@ -0,0 +1,252 @@
+use crate::process::handle_interruptions;
+use serde::Deserialize;
+use std::env::temp_dir;
+use std::fs::{create_dir_all, remove_dir_all, remove_file, write};
+use std::io::{Error, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::process::{Command, id};
+use std::str::FromStr;
+use std::sync::{Mutex, PoisonError};
+
+/// The name of the file, in the git directory, holding the unstaged changes while the hooks run.
+pub const UNSTAGED_PATCH: &str = "breathes-unstaged.patch";
+
+///
+/// What the hooks check: the working tree, or the staged state which will be committed.
+///
+/// # Example
+/// ```rust
+/// use breathes::snapshot::SnapshotMode;
+///
+/// assert_eq!("stash".parse::<SnapshotMode>().unwrap(), SnapshotMode::Stash);
+/// assert!("copy".parse::<SnapshotMode>().is_err());
+/// ```
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SnapshotMode {
+    /// Check the working tree as it is.
+    #[default]
+    None,
+    /// Set the unstaged changes aside, check the working tree, then put them back.
+    Stash,
+    /// Check a copy of the index, exported to a temporary directory.
+    Index,
+}
+
+impl FromStr for SnapshotMode {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "none" => Ok(Self::None),
+            "stash" => Ok(Self::Stash),
+            "index" => Ok(Self::Index),
+            _ => Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("unknown snapshot mode '{value}', expected none, stash or index"),
+            )),
+        }
+    }
+}
+
+/// What must be undone once the hooks have run.
+#[derive(Debug)]
+struct Restore {
+    top: PathBuf,
+    patch: Option<PathBuf>,
+    checkout: Option<PathBuf>,
+}
+
+/// The snapshot being checked, restored by the Ctrl-C handler if the run is interrupted.
+static ACTIVE: Mutex<Option<Restore>> = Mutex::new(None);
+
+///
+/// The staged state of the repository, checked by the hooks instead of the working tree.
+///
+/// With [`SnapshotMode::Stash`], the unstaged changes of tracked files are saved as a patch in
+/// the git directory and removed from the working tree. Untracked files stay in place. With
+/// [`SnapshotMode::Index`], the index is exported to a temporary directory, in which the
+/// hooks run; build artifacts and dependencies which are not committed are absent from it.
+///
+/// The working tree is restored when the snapshot is dropped, including when a hook fails,
+/// and when the run is interrupted with Ctrl-C. If the unstaged changes cannot be applied
+/// back, they are kept in [`UNSTAGED_PATCH`] to be applied with `git apply`.
+///
+/// # Example
+/// ```rust
+/// use breathes::snapshot::{Snapshot, SnapshotMode};
+/// use std::path::Path;
+///
+/// let snapshot = Snapshot::take(SnapshotMode::None).unwrap();
+/// assert_eq!(snapshot.dir(), Path::new("."));
+/// ```
+#[derive(Debug)]
+pub struct Snapshot {
+    dir: PathBuf,
+    active: bool,
+}
+
+impl Snapshot {
+    ///
+    /// Takes a snapshot of the staged state of the repository containing the current directory.
+    ///
+    /// # Errors
+    /// - If the current directory is not in a git repository, or git fails.
+    /// - If the unstaged changes of an interrupted run were not restored yet.
+    /// - If another snapshot is being checked.
+    ///
+    pub fn take(mode: SnapshotMode) -> Result<Self, Error> {
+        if mode == SnapshotMode::None {
+            return Ok(Self {
+                dir: PathBuf::from("."),
+                active: false,
+            });
+        }
+        let mut active = ACTIVE.lock().unwrap_or_else(PoisonError::into_inner);
+        if active.is_some() {
+            return Err(Error::other(
+                "a snapshot of the repository is already taken",
+            ));
+        }
+        let top = PathBuf::from(git_text(Path::new("."), &["rev-parse", "--show-toplevel"])?);
+        let mut restore = Restore {
+            top: top.clone(),
+            patch: None,
+            checkout: None,
+        };
+        let dir = match mode {
+            SnapshotMode::None => PathBuf::from("."),
+            SnapshotMode::Stash => {
+                let git_dir = git_text(&top, &["rev-parse", "--absolute-git-dir"])?;
+                let patch = Path::new(&git_dir).join(UNSTAGED_PATCH);
+                if patch.exists() {
+                    return Err(Error::other(format!(
+                        "the unstaged changes of an interrupted run are saved in {}: apply them with `git apply` and remove the file",
+                        patch.display()
+                    )));
+                }
+                let diff = git(
+                    &top,
+                    &[
+                        "diff",
+                        "--binary",
+                        // `git apply` strips these prefixes, whatever `diff.noprefix` says.
+                        "--src-prefix=a/",
+                        "--dst-prefix=b/",
+                        "--no-color",
+                        "--no-ext-diff",
+                        "--no-textconv",
+                    ],
+                )?;
+                if !diff.is_empty() {
+                    write(&patch, diff)?;
+                    restore.patch = Some(patch);
+                    if let Err(e) = git(&top, &["checkout", "--", "."]) {
+                        let _ = undo(restore);
+                        return Err(e);
+                    }
+                }
+                PathBuf::from(".")
+            }
+            SnapshotMode::Index => {
+                let checkout = temp_dir().join(format!("breathes-index-{}", id()));
+                create_dir_all(&checkout)?;
+                restore.checkout = Some(checkout.clone());
+                let prefix = format!("--prefix={}/", checkout.display());
+                if let Err(e) = git(&top, &["checkout-index", "--all", &prefix]) {
+                    let _ = undo(restore);
+                    return Err(e);
+                }
+                let subdirectory = git_text(Path::new("."), &["rev-parse", "--show-prefix"])?;
+                checkout.join(subdirectory)
+            }
+        };
+        *active = Some(restore);
+        drop(active);
+        handle_interruptions();
+        Ok(Self { dir, active: true })
+    }
+
+    ///
+    /// Returns the directory in which the hooks check the snapshot, `.` when they check the
+    /// working tree.
+    ///
+    #[must_use]
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    ///
+    /// Restores the working tree, reporting the failures ignored when the snapshot is dropped.
+    ///
+    /// # Errors
+    /// - If the unstaged changes cannot be applied back, or the temporary checkout cannot be
+    ///   removed.
+    ///
+    pub fn restore(mut self) -> Result<(), Error> {
+        if !std::mem::take(&mut self.active) {
+            return Ok(());
+        }
+        // The lock is held while restoring, so that an interruption waits for the restore.
+        let mut active = ACTIVE.lock().unwrap_or_else(PoisonError::into_inner);
+        active.take().map_or(Ok(()), undo)
+    }
+}
+
+impl Drop for Snapshot {
+    fn drop(&mut self) {
+        if !self.active {
+            return;
+        }
+        let mut active = ACTIVE.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(Err(e)) = active.take().map(undo) {
+            eprintln!("breathes: {e}");
+        }
+    }
+}
+
+///
+/// Restores the working tree of the snapshot being checked, once the run is interrupted and
+/// its hooks are killed.
+///
+pub(crate) fn restore_interrupted() {
+    let mut active = ACTIVE.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some(Err(e)) = active.take().map(undo) {
+        eprintln!("breathes: {e}");
+    }
+}
+
+fn undo(restore: Restore) -> Result<(), Error> {
+    if let Some(checkout) = &restore.checkout {
+        remove_dir_all(checkout)?;
+    }
+    if let Some(patch) = &restore.patch {
+        // The changes made by the hooks are kept: `git apply` puts the unstaged changes back
+        // over them, or changes nothing if they overlap.
+        let path = patch.to_string_lossy();
+        git(&restore.top, &["apply", "--whitespace=nowarn", &path]).map_err(|e| {
+            Error::other(format!(
+                "the unstaged changes could not be restored over the changes made by the hooks ({e}), they are saved in {path}"
+            ))
+        })?;
+        remove_file(patch)?;
+    }
+    Ok(())
+}
+
+fn git(dir: &Path, args: &[&str]) -> Result<Vec<u8>, Error> {
+    let output = Command::new("git").current_dir(dir).args(args).output()?;
+    if !output.status.success() {
+        return Err(Error::other(format!(
+            "git {} failed: {}",
+            args.first().copied().unwrap_or_default(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(output.stdout)
+}
+
+fn git_text(dir: &Path, args: &[&str]) -> Result<String, Error> {
+    let output = git(dir, args)?;
+    Ok(String::from_utf8_lossy(&output).trim().to_string())
+}
@@ -0,0 +1,50 @@
+use std::fs::{create_dir_all, remove_dir_all, write};
+use std::path::{Path, PathBuf};
+use std::process;
+
+///
+/// A directory of the unit tests, removed when dropped.
+///
+/// Its name holds the name of the test and the id of the process, so that the tests and
+/// concurrent runs of the test suite never share a fixture.
+///
+pub struct Fixture {
+    dir: PathBuf,
+}
+
+impl Fixture {
+    ///
+    /// Creates an empty directory for the test `name`.
+    ///
+    pub fn new(name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("breathes-{name}-{}", process::id()));
+        let _ = remove_dir_all(&dir);
+        create_dir_all(&dir).expect("Failed to create the fixture");
+        Self { dir }
+    }
+
+    ///
+    /// Writes `content` to the file at `path`, creating its parent directories.
+    ///
+    pub fn write(&self, path: &str, content: &str) -> &Self {
+        let path = self.dir.join(path);
+        if let Some(parent) = path.parent() {
+            create_dir_all(parent).expect("Failed to create the fixture");
+        }
+        write(path, content).expect("Failed to write the fixture");
+        self
+    }
+
+    ///
+    /// The directory of the fixture.
+    ///
+    pub fn path(&self) -> &Path {
+        &self.dir
+    }
+}
+
+impl Drop for Fixture {
+    fn drop(&mut self) {
+        let _ = remove_dir_all(&self.dir);
+    }
+}
@@ -0,0 +1,574 @@
+use crate::python::scripts;
+use crate::report::HookStatus;
+use serde::Deserialize;
+use std::env::{split_paths, var, var_os};
+use std::fmt::{Display, Formatter};
+use std::fs::{metadata, read_to_string};
+use std::io::{Error, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::str::FromStr;
+
+/// The commands built into the shells running the hooks, which are not looked up.
+const SHELL_BUILTINS: [&str; 14] = [
+    ".", "[", "call", "cd", "echo", "eval", "exec", "exit", "export", "false", "set", "source",
+    "test", "true",
+];
+
+/// The subcommands built into cargo. The others run a `cargo-<name>` executable.
+const CARGO_COMMANDS: [&str; 36] = [
+    "add",
+    "bench",
+    "build",
+    "check",
+    "clean",
+    "config",
+    "doc",
+    "fetch",
+    "fix",
+    "generate-lockfile",
+    "help",
+    "info",
+    "init",
+    "install",
+    "locate-project",
+    "login",
+    "logout",
+    "metadata",
+    "new",
+    "owner",
+    "package",
+    "pkgid",
+    "publish",
+    "remove",
+    "report",
+    "run",
+    "rustc",
+    "rustdoc",
+    "search",
+    "test",
+    "tree",
+    "uninstall",
+    "update",
+    "vendor",
+    "verify-project",
+    "yank",
+];
+
+/// The subcommands built into the .NET SDK. The others run a `dotnet-<name>` tool.
+const DOTNET_COMMANDS: [&str; 22] = [
+    "add",
+    "build",
+    "build-server",
+    "clean",
+    "format",
+    "help",
+    "list",
+    "msbuild",
+    "new",
+    "nuget",
+    "pack",
+    "package",
+    "publish",
+    "reference",
+    "remove",
+    "restore",
+    "run",
+    "sdk",
+    "sln",
+    "test",
+    "tool",
+    "workload",
+];
+
+/// The subcommands built into Yarn. The others run an executable of the dependencies.
+const YARN_COMMANDS: [&str; 20] = [
+    "add",
+    "audit",
+    "bin",
+    "cache",
+    "config",
+    "dlx",
+    "exec",
+    "info",
+    "init",
+    "install",
+    "link",
+    "npm",
+    "outdated",
+    "pack",
+    "remove",
+    "run",
+    "unlink",
+    "up",
+    "upgrade",
+    "workspace",
+];
+
+/// The Python program exiting with an error when the module given as argument is not found.
+const FIND_MODULE: &str =
+    "import importlib.util, sys; sys.exit(importlib.util.find_spec(sys.argv[1]) is None)";
+
+/// The R expression exiting with an error when the package given as argument is not installed.
+const FIND_PACKAGE: &str = "quit(status = !requireNamespace(commandArgs(TRUE)[1], quietly = TRUE))";
+
+/// The commands installing the tools used by the built-in hooks.
+const INSTALL_HINTS: [(&str, &str); 18] = [
+    ("cargo", "install Rust with rustup, see https://rustup.rs"),
+    ("cargo-clippy", "rustup component add clippy"),
+    ("cargo-fmt", "rustup component add rustfmt"),
+    ("swiftformat", "brew install swiftformat"),
+    ("dotnet", "install the .NET SDK, see https://dot.net"),
+    ("node", "install Node.js, see https://nodejs.org"),
+    ("npm", "install Node.js, see https://nodejs.org"),
+    ("npx", "install Node.js, see https://nodejs.org"),
+    ("pnpm", "npm install --global pnpm"),
+    ("yarn", "npm install --global yarn"),
+    ("tsc", "npm install --save-dev typescript"),
+    ("prettier", "npm install --save-dev prettier"),
+    ("pytest", "pip install pytest"),
+    ("ruff", "pip install ruff"),
+    ("mypy", "pip install mypy"),
+    ("pip-audit", "pip install pip-audit"),
+    ("go", "install Go, see https://go.dev/dl"),
+    ("Rscript", "install R, see https://cran.r-project.org"),
+];
+
+///
+/// What happens to a hook whose tool is not installed, set with `[run] missing_tools`.
+///
+/// # Example
+/// ```rust
+/// use breathes::tools::MissingTools;
+///
+/// assert_eq!("skip".parse::<MissingTools>().unwrap(), MissingTools::Skip);
+/// assert!("ignore".parse::<MissingTools>().is_err());
+/// ```
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MissingTools {
+    /// Report the hook as `tool missing`, which fails the run.
+    #[default]
+    Fail,
+    /// Skip the hook, and the hooks depending on it.
+    Skip,
+}
+
+impl MissingTools {
+    ///
+    /// Returns the status of a hook whose tool is not installed.
+    ///
+    /// # Example
+    /// ```rust
+    /// use breathes::hooks::{Language, OutputFormat};
+    /// use breathes::report::{HookReport, HookStatus, LanguageReport, RunReport};
+    /// use breathes::tools::MissingTools;
+    /// use std::time::Duration;
+    ///
+    /// let run = |missing_tools: MissingTools| RunReport {
+    ///     languages: vec![LanguageReport {
+    ///         language: Language::Rust,
+    ///         root: ".".into(),
+    ///         hooks: vec![HookReport {
+    ///             id: "rust-audit".into(),
+    ///             description: "Checks for vulnerabilities".into(),
+    ///             status: missing_tools.status(),
+    ///             exit_code: None,
+    ///             signal: None,
+    ///             duration: Duration::ZERO,
+    ///             stdout: "breathes/Rust/stdout/audit.log".into(),
+    ///             stderr: "breathes/Rust/stderr/audit.log".into(),
+    ///             message: "`cargo-audit` is not installed".into(),
+    ///             output: OutputFormat::Text,
+    ///         }],
+    ///         duration: Duration::ZERO,
+    ///     }],
+    ///     duration: Duration::ZERO,
+    /// };
+    /// let failed = run(MissingTools::Fail);
+    /// assert_eq!(failed.count(HookStatus::ToolMissing), 1);
+    /// assert!(!failed.success());
+    /// assert!(run(MissingTools::Skip).success());
+    /// ```
+    #[must_use]
+    pub const fn status(self) -> HookStatus {
+        match self {
+            Self::Fail => HookStatus::ToolMissing,
+            Self::Skip => HookStatus::Skipped,
+        }
+    }
+}
+
+impl FromStr for MissingTools {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "fail" => Ok(Self::Fail),
+            "skip" => Ok(Self::Skip),
+            _ => Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("unknown missing tools policy '{value}', expected fail or skip"),
+            )),
+        }
+    }
+}
+
+///
+/// A tool needed by the command of a hook and not installed.
+///
+/// * `tool`: The executable not found, such as `cargo-audit`.
+/// * `hint`: How to install it.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MissingTool {
+    pub tool: String,
+    pub hint: String,
+}
+
+impl MissingTool {
+    fn new(tool: &str) -> Self {
+        Self {
+            tool: tool.to_string(),
+            hint: install_hint(tool),
+        }
+    }
+}
+
+impl Display for MissingTool {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "`{}` is not installed, {}", self.tool, self.hint)
+    }
+}
+
+///
+/// Returns how to install `tool`.
+///
+/// # Example
+/// ```rust
+/// use breathes::tools::install_hint;
+///
+/// assert_eq!(install_hint("cargo-audit"), "install it with `cargo install cargo-audit`");
+/// assert_eq!(install_hint("cargo-fmt"), "install it with `rustup component add rustfmt`");
+/// ```
+#[must_use]
+pub fn install_hint(tool: &str) -> String {
+    if let Some((_, hint)) = INSTALL_HINTS.iter().find(|(name, _)| *name == tool) {
+        if hint.starts_with("install ") {
+            return (*hint).to_string();
+        }
+        return format!("install it with `{hint}`");
+    }
+    if tool.starts_with("cargo-") {
+        return format!("install it with `cargo install {tool}`");
+    }
+    if tool.starts_with("dotnet-") {
+        return format!("install it with `dotnet tool install --global {tool}`");
+    }
+    String::from("install it and make sure it is on the PATH")
+}
+
+///
+/// Returns the path of the executable `name` found on the `PATH`.
+///
+/// On Windows, the extensions of `PATHEXT` are tried when `name` has none.
+///
+#[must_use]
+pub fn find_executable(name: &str) -> Option<PathBuf> {
+    let path = var_os("PATH")?;
+    split_paths(&path).find_map(|dir| find_executable_in(&dir, name))
+}
+
+///
+/// Finds the tool `command` needs which is not installed, `command` running in `dir`.
+///
+/// The program of the command must be on the `PATH`, or exist relative to `dir` when it is
+/// a path. A cargo or .NET subcommand which is not built in must be installed as
+/// `cargo-<name>` or `dotnet-<name>`. A program run with `npx`, `pnpm exec`, `bunx` or
+/// `yarn` must be installed in `node_modules/.bin`, unless Yarn Plug'n'Play installs the
+/// dependencies. A program run with `uv run`, `poetry run` or `pipenv run` must be installed
+/// in the environment of the project, once it exists, and a module run with `python -m` must
+/// be importable by the interpreter. The R packages called as `package::function` in the
+/// expression of `Rscript -e` must be installed.
+///
+/// Only the first command of a shell pipeline or list is checked.
+///
+/// # Example
+/// ```rust
+/// use breathes::tools::missing_tool;
+/// use std::path::Path;
+///
+/// let here = Path::new(".");
+/// assert_eq!(missing_tool("cargo check", here), None);
+/// let missing = missing_tool("breathes-not-installed --check", here).unwrap();
+/// assert_eq!(missing.tool, "breathes-not-installed");
+/// let missing = missing_tool("cargo breathes-not-installed", here).unwrap();
+/// assert_eq!(missing.tool, "cargo-breathes-not-installed");
+/// assert_eq!(missing_tool("cargo +stable --version", here), None);
+/// let missing = missing_tool("cargo +nightly breathes-not-installed", here).unwrap();
+/// assert_eq!(missing.tool, "cargo-breathes-not-installed");
+///
+/// # #[cfg(unix)]
+/// if breathes::tools::find_executable("python3").is_some() {
+///     assert_eq!(missing_tool("python3 -m json.tool --help", here), None);
+///     let missing = missing_tool("python3 -m breathes_not_installed", here).unwrap();
+///     assert_eq!(missing.tool, "breathes_not_installed");
+/// }
+/// # #[cfg(unix)]
+/// if breathes::tools::find_executable("Rscript").is_some() {
+///     let missing = missing_tool("Rscript -e \"breathesNotInstalled::run()\"", here).unwrap();
+///     assert_eq!(missing.tool, "breathesNotInstalled");
+/// }
+/// ```
+#[must_use]
+pub fn missing_tool(command: &str, dir: &Path) -> Option<MissingTool> {
+    let words: Vec<&str> = command
+        .split_whitespace()
+        .skip_while(|word| is_assignment(word))
+        .map(|word| word.trim_matches(|c| c == '\'' || c == '"'))
+        .collect();
+    let (&program, args) = words.split_first()?;
+    if SHELL_BUILTINS.contains(&program) {
+        return None;
+    }
+    let found = if program.contains(['/', '\\']) {
+        find_executable_in(dir, program).is_some()
+    } else {
+        find_executable(program).is_some()
+    };
+    if !found {
+        return Some(MissingTool::new(program));
+    }
+    let name = Path::new(program)
+        .file_stem()
+        .map_or(program.into(), |stem| stem.to_string_lossy());
+    // `cargo +nightly clippy` selects a toolchain before the subcommand.
+    let args = match (name.as_ref(), args.split_first()) {
+        ("cargo", Some((toolchain, rest))) if toolchain.starts_with('+') => rest,
+        _ => args,
+    };
+    let arg = |i: usize| args.get(i).copied().filter(|arg| !arg.starts_with('-'));
+    match (name.as_ref(), arg(0)) {
+        ("cargo", Some(subcommand)) if !CARGO_COMMANDS.contains(&subcommand) => {
+            let tool = format!("cargo-{subcommand}");
+            let cargo_bin = var_os("CARGO_HOME")
+                .map(PathBuf::from)
+                .or_else(|| home().map(|home| home.join(".cargo")))
+                .map(|cargo_home| cargo_home.join("bin"));
+            let installed = find_executable(&tool).is_some()
+                || cargo_bin.is_some_and(|bin| find_executable_in(&bin, &tool).is_some());
+            (!installed).then(|| MissingTool::new(&tool))
+        }
+        ("dotnet", Some(subcommand)) if !DOTNET_COMMANDS.contains(&subcommand) => {
+            let tool = format!("dotnet-{subcommand}");
+            let global = home().map(|home| home.join(".dotnet").join("tools"));
+            let local = dir.ancestors().any(|dir| {
+                read_to_string(dir.join(".config").join("dotnet-tools.json"))
+                    .is_ok_and(|manifest| manifest.contains(&format!("\"{tool}\"")))
+            });
+            let installed = local
+                || find_executable(&tool).is_some()
+                || global.is_some_and(|global| find_executable_in(&global, &tool).is_some());
+            (!installed).then(|| MissingTool::new(&tool))
+        }
+        ("npx" | "bunx", Some(tool)) => node_binary(tool, dir),
+        ("pnpm", Some("exec")) => arg(1).and_then(|tool| node_binary(tool, dir)),
+        ("yarn", Some(tool)) if !YARN_COMMANDS.contains(&tool) => node_binary(tool, dir),
+        ("uv" | "poetry" | "pipenv", Some("run")) => {
+            let tool = args[1..].iter().find(|arg| !arg.starts_with('-'))?;
+            environment_tool(&name, tool, dir)
+        }
+        (python, None) if is_python(python) && args.first() == Some(&"-m") => {
+            python_module(program, args.get(1)?, dir)
+        }
+        ("Rscript", None) if args.first() == Some(&"-e") => r_package(program, &args[1..], dir),
+        _ => None,
+    }
+}
+
+/// Finds the tool run with `uv run`, `poetry run` or `pipenv run` in the environment of
+/// `manager`, or on the `PATH`. Nothing is reported while the environment does not exist.
+fn environment_tool(manager: &str, tool: &str, dir: &Path) -> Option<MissingTool> {
+    let environment = environment_dir(manager, dir)?;
+    let installed = find_executable_in(&scripts(&environment), tool).is_some()
+        || find_executable(tool).is_some();
+    let add = match manager {
+        "uv" => "uv add --dev",
+        "poetry" => "poetry add --group dev",
+        _ => "pipenv install --dev",
+    };
+    (!installed).then(|| MissingTool {
+        tool: tool.to_string(),
+        hint: format!("install it with `{add} {tool}`"),
+    })
+}
+
+/// Returns the virtual environment managed by `manager` for the project in `dir`.
+fn environment_dir(manager: &str, dir: &Path) -> Option<PathBuf> {
+    let local = (manager == "uv")
+        .then(|| var_os("UV_PROJECT_ENVIRONMENT"))
+        .flatten()
+        .map_or_else(|| dir.join(".venv"), |environment| dir.join(environment));
+    if local.join("pyvenv.cfg").is_file() {
+        return Some(local);
+    }
+    let args: &[&str] = match manager {
+        "poetry" => &["env", "info", "--path"],
+        "pipenv" => &["--venv"],
+        _ => return None,
+    };
+    let output = Command::new(manager)
+        .current_dir(dir)
+        .args(args)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    let environment = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+    (output.status.success() && environment.join("pyvenv.cfg").is_file()).then_some(environment)
+}
+
+/// Finds the `module` run with `python -m`, by asking the interpreter `python` to import it.
+fn python_module(python: &str, module: &str, dir: &Path) -> Option<MissingTool> {
+    let interpreter = if python.contains(['/', '\\']) {
+        dir.join(python)
+    } else {
+        PathBuf::from(python)
+    };
+    let status = Command::new(interpreter)
+        .current_dir(dir)
+        .args(["-c", FIND_MODULE, module])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .ok()?;
+    (!status.success()).then(|| MissingTool {
+        tool: module.to_string(),
+        hint: format!("install it with `{python} -m pip install {module}`"),
+    })
+}
+
+/// Finds the first R package called as `package::function` in `expression` which `rscript`
+/// cannot load.
+fn r_package(rscript: &str, expression: &[&str], dir: &Path) -> Option<MissingTool> {
+    let packages = expression.iter().filter_map(|word| {
+        let (before, _) = word.split_once("::")?;
+        let start = before
+            .rfind(|c: char| !c.is_ascii_alphanumeric() && c != '.')
+            .map_or(0, |i| i + 1);
+        Some(&before[start..]).filter(|package| !package.is_empty())
+    });
+    let interpreter = if rscript.contains(['/', '\\']) {
+        dir.join(rscript)
+    } else {
+        PathBuf::from(rscript)
+    };
+    for package in packages {
+        let status = Command::new(&interpreter)
+            .current_dir(dir)
+            .args(["-e", FIND_PACKAGE, package])
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .ok()?;
+        if !status.success() {
+            return Some(MissingTool {
+                tool: package.to_string(),
+                hint: format!("install it with `{rscript} -e \"install.packages('{package}')\"`"),
+            });
+        }
+    }
+    None
+}
+
+/// Whether the program `name` is a Python interpreter, such as `python3.12`.
+fn is_python(name: &str) -> bool {
+    name == "py"
+        || name
+            .strip_prefix("python")
+            .is_some_and(|version| version.chars().all(|c| c.is_ascii_digit() || c == '.'))
+}
+
+/// Finds the executable of the dependencies `tool`, in `node_modules/.bin`.
+fn node_binary(tool: &str, dir: &Path) -> Option<MissingTool> {
+    let installed = dir.ancestors().any(|dir| {
+        dir.join(".pnp.cjs").is_file()
+            || find_executable_in(&dir.join("node_modules").join(".bin"), tool).is_some()
+    });
+    (!installed).then(|| MissingTool::new(tool))
+}
+
+///
+/// Returns the path of the executable `name` found in `dir`.
+///
+/// On Windows, the extensions of `PATHEXT` are tried when `name` has none.
+///
+#[must_use]
+pub fn find_executable_in(dir: &Path, name: &str) -> Option<PathBuf> {
+    let path = dir.join(name);
+    if !cfg!(windows) || path.extension().is_some() {
+        return is_executable(&path).then_some(path);
+    }
+    let extensions = var("PATHEXT").unwrap_or_else(|_| String::from(".COM;.EXE;.BAT;.CMD"));
+    extensions
+        .split(';')
+        .map(|extension| path.with_extension(extension.trim_start_matches('.')))
+        .find(|path| path.is_file())
+}
+
+/// Whether `path` is a file which can be executed: on unix, one of its execute bits is set.
+fn is_executable(path: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        metadata(path)
+            .is_ok_and(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+    }
+    #[cfg(not(unix))]
+    {
+        path.is_file()
+    }
+}
+
+fn home() -> Option<PathBuf> {
+    var_os("HOME")
+        .or_else(|| var_os("USERPROFILE"))
+        .map(PathBuf::from)
+}
+
+/// Whether `word` sets an environment variable for the command, as in `RUST_LOG=debug cmd`.
+fn is_assignment(word: &str) -> bool {
+    word.split_once('=').is_some_and(|(name, _)| {
+        !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    })
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::missing_tool;
+    use crate::testing::Fixture;
+
+    #[test]
+    fn missing_tool_finds_the_r_packages_not_installed() {
+        use std::fs::{Permissions, set_permissions};
+        use std::os::unix::fs::PermissionsExt;
+
+        let fixture = Fixture::new("r-packages");
+        // Only `lintr` is missing: its name follows `-e` and the expression.
+        fixture.write("Rscript", "#!/bin/sh\ntest \"$3\" != lintr\n");
+        set_permissions(
+            fixture.path().join("Rscript"),
+            Permissions::from_mode(0o755),
+        )
+        .unwrap();
+
+        let command = "./Rscript -e \"lints <- lintr::lint_dir(); print(lints)\"";
+        let missing = missing_tool(command, fixture.path()).unwrap();
+        assert_eq!(missing.tool, "lintr");
+        assert!(missing.hint.contains("install.packages('lintr')"));
+        let command = "./Rscript -e \"styler::style_dir(dry = 'fail')\"";
+        assert_eq!(missing_tool(command, fixture.path()), None);
+    }
+}
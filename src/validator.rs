@@ -1,3 +1,4 @@
+use crate::config::{Config, ScopePolicy};
 use hunspell_rs::{CheckResult, Hunspell};
 use inquire::CustomUserError;
 use inquire::validator::ErrorMessage::Custom;
@@ -5,12 +6,20 @@ use inquire::validator::Validation;
 use once_cell::unsync::Lazy;
 use regex::Regex;
 #[doc = "List of valid commit types"]
-pub const VALID_TYPES: [&str; 10] = [
-    "feat", "fix", "docs", "style", "refactor", "perf", "test", "chore", "ci", "build",
+pub const VALID_TYPES: [&str; 11] = [
+    "feat", "fix", "docs", "style", "refactor", "perf", "test", "chore", "ci", "build", "revert",
 ];
+#[doc = "Maximum length of a commit summary"]
+pub const SUMMARY_MAX_LENGTH: usize = 50;
+#[doc = "Maximum length of a line in a commit body"]
+pub const BODY_MAX_LINE_LENGTH: usize = 72;
+#[doc = "Hunspell dictionary used by the spelling validator"]
+pub const DICTIONARY: &str = "dict/en_US.dic";
+#[doc = "Hunspell affix file used by the spelling validator"]
+pub const AFFIXES: &str = "dict/en_US.aff";
 thread_local! {
     static HUNSPELL: Lazy<Hunspell> = Lazy::new(|| {
-        Hunspell::new("dict/en_US.dic", "dict/en_US.aff")
+        Hunspell::new(DICTIONARY, AFFIXES)
     });
 }
 
@@ -52,14 +61,42 @@ pub fn validate_password(input: &str) -> Result<Validation, CustomUserError> {
 /// # Errors
 /// on bad input
 pub fn validate_commit_type(input: &str) -> Result<Validation, CustomUserError> {
-    // La liste des types autorisés (basée sur Conventional Commits)
+    check_commit_type(input, &VALID_TYPES)
+}
+
+/// Build a commit type validator using the types of the project configuration
+///
+/// ```rust
+/// use breathes::config::Config;
+/// use breathes::validator::commit_type_validator;
+/// use inquire::validator::Validation;
+///
+/// let config: Config = r#"types = ["feat", "fix"]"#.parse().unwrap();
+/// let validate = commit_type_validator(&config);
+/// assert_eq!(validate("fix").unwrap(), Validation::Valid);
+/// assert_ne!(validate("docs").unwrap(), Validation::Valid);
+/// ```
+pub fn commit_type_validator(
+    config: &Config,
+) -> impl Fn(&str) -> Result<Validation, CustomUserError> + Clone + 'static {
+    let types = config.types.clone();
+    move |input: &str| check_commit_type(input, &types)
+}
 
+fn check_commit_type<T: AsRef<str>>(
+    input: &str,
+    types: &[T],
+) -> Result<Validation, CustomUserError> {
     let trimmed_input = input.trim();
 
-    if VALID_TYPES.contains(&trimmed_input) {
+    if types.iter().any(|t| t.as_ref() == trimmed_input) {
         Ok(Validation::Valid)
     } else {
-        let types_str = VALID_TYPES.join(", ");
+        let types_str = types
+            .iter()
+            .map(AsRef::as_ref)
+            .collect::<Vec<_>>()
+            .join(", ");
 
         let message = Custom(format!(
             "Type '{trimmed_input}' invalide. Must be one of: {types_str}"
@@ -67,6 +104,102 @@ pub fn validate_commit_type(input: &str) -> Result<Validation, CustomUserError>
         Ok(Validation::Invalid(message))
     }
 }
+/// Validate the scope of a commit against the scopes and the scope policy of the project
+///
+/// An empty input means that no scope is given. With `multiple_scopes`, the input may
+/// name several comma-separated scopes, each of which is checked.
+///
+/// # Errors
+/// on bad input
+///
+/// ```rust
+/// use breathes::config::Config;
+/// use breathes::validator::validate_scope;
+/// use inquire::validator::{ErrorMessage, Validation};
+///
+/// let config: Config = r#"scopes = ["lib", "api"]"#.parse().unwrap();
+/// assert_eq!(validate_scope("api", &config).unwrap(), Validation::Valid);
+/// assert_eq!(validate_scope("", &config).unwrap(), Validation::Valid);
+/// let Validation::Invalid(ErrorMessage::Custom(message)) = validate_scope("lbi", &config).unwrap() else {
+///     panic!("'lbi' is not a scope");
+/// };
+/// assert!(message.contains("Did you mean 'lib'?"));
+/// ```
+pub fn validate_scope(input: &str, config: &Config) -> Result<Validation, CustomUserError> {
+    let input = input.trim();
+    if input.is_empty() {
+        if config.scope_policy == ScopePolicy::Required {
+            return Ok(Validation::Invalid(Custom(String::from(
+                "A scope is required",
+            ))));
+        }
+        return Ok(Validation::Valid);
+    }
+    if config.scope_policy == ScopePolicy::Forbidden {
+        return Ok(Validation::Invalid(Custom(String::from(
+            "Scopes are not allowed in this repository",
+        ))));
+    }
+    let scopes: Vec<&str> = input.split(',').map(str::trim).collect();
+    if scopes.len() > 1 && !config.multiple_scopes {
+        return Ok(Validation::Invalid(Custom(String::from(
+            "Only one scope is allowed",
+        ))));
+    }
+    for scope in scopes {
+        if scope.is_empty() {
+            return Ok(Validation::Invalid(Custom(String::from(
+                "Scopes cannot be empty",
+            ))));
+        }
+        if config.scopes.is_empty() || config.scopes.iter().any(|s| s == scope) {
+            continue;
+        }
+        let scopes_str = config.scopes.join(", ");
+        let message = match closest(scope, &config.scopes) {
+            Some(suggestion) => format!(
+                "Scope '{scope}' invalide. Did you mean '{suggestion}'? Must be one of: {scopes_str}"
+            ),
+            None => format!("Scope '{scope}' invalide. Must be one of: {scopes_str}"),
+        };
+        return Ok(Validation::Invalid(Custom(message)));
+    }
+    Ok(Validation::Valid)
+}
+
+/// Build a scope validator using the scopes and the scope policy of the project configuration
+pub fn scope_validator(
+    config: &Config,
+) -> impl Fn(&str) -> Result<Validation, CustomUserError> + Clone + 'static {
+    let config = config.clone();
+    move |input: &str| validate_scope(input, &config)
+}
+
+/// Returns the candidate closest to `input`, if it is close enough to be a typo
+fn closest<'a>(input: &str, candidates: &'a [String]) -> Option<&'a str> {
+    candidates
+        .iter()
+        .map(|candidate| (edit_distance(input, candidate), candidate))
+        .filter(|(distance, candidate)| *distance <= (candidate.chars().count() / 3).max(2))
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate.as_str())
+}
+
+/// Levenshtein distance between two strings, counted in characters
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
 ///
 /// # Validate that the input is a valid spelling
 ///
@@ -100,7 +233,7 @@ pub fn validate_spelling(input: &str) -> Result<Validation, CustomUserError> {
 /// # Errors
 /// on bad input
 pub fn validate_summary_length(input: &str) -> Result<Validation, CustomUserError> {
-    const MAX_LENGTH: usize = 50;
+    const MAX_LENGTH: usize = SUMMARY_MAX_LENGTH;
     let len = input.trim().len();
 
     if len > MAX_LENGTH {
@@ -127,7 +260,7 @@ pub fn validate_summary_punctuation(input: &str) -> Result<Validation, CustomUse
 /// # Errors
 /// on bad input
 pub fn validate_body_line_length(input: &str) -> Result<Validation, CustomUserError> {
-    const MAX_LINE_LENGTH: usize = 72;
+    const MAX_LINE_LENGTH: usize = BODY_MAX_LINE_LENGTH;
 
     for line in input.lines() {
         if line.len() > MAX_LINE_LENGTH {